@@ -1,3 +1,5 @@
 pub const COULD_NOT_PARSE_BOOTSTRAP_SERVER: u8 = 100;
+pub const COULD_NOT_PARSE_REFRESH_INTERVAL: u8 = 101;
+pub const COULD_NOT_LOAD_PROFILE: u8 = 102;
 
 pub const KAFKA_API_VERIFICATION_FAIL: u8 = 200;
@@ -1,11 +1,14 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 use crate::api_client::ApiClientProvider;
 use crate::api_client::ApiClientTrait;
 use crate::api_client::ApiRequestError;
 use crate::kafka_protocol::protocol_request::Request;
 use crate::kafka_protocol::protocol_response::Response;
+use crate::kafka_protocol::protocol_responses::findcoordinator_response::Coordinator;
 use crate::kafka_protocol::protocol_serializable::ProtocolDeserializable;
 use crate::kafka_protocol::protocol_serializable::ProtocolSerializable;
 use crate::KafkaServerAddr;
@@ -13,9 +16,14 @@ use crate::IO;
 
 use crate::event_bus;
 use crate::event_bus::*;
+use crate::state::Cluster;
 use crate::state::State;
 use crate::state::StateFNError;
 use crate::state::{CurrentView, DialogMessage};
+use crate::state::{OffsetRates, OffsetSnapshot};
+use crate::util::health;
+use crate::util::health::PartitionHealth;
+use crate::util::utils;
 
 struct FakeApiClient(HashMap<i16, Vec<u8>>); // ApiKey => Byte Response
 
@@ -30,6 +38,37 @@ impl ApiClientTrait for FakeApiClient {
     }
 }
 
+/// Records the ApiKey and server (host:port) of each request
+struct RecordingApiClient(FakeApiClient, Arc<Mutex<Vec<(i16, String)>>>);
+
+impl ApiClientTrait for RecordingApiClient {
+    fn request<T, U>(&self, server_addr: &KafkaServerAddr, request: Request<T>) -> Result<Response<U>, ApiRequestError>
+    where
+        T: ProtocolSerializable,
+        Vec<u8>: ProtocolDeserializable<Response<U>>,
+    {
+        self.1.lock().unwrap().push((request.header.api_key, format!("{}:{}", server_addr.host, server_addr.port)));
+        self.0.request(server_addr, request)
+    }
+}
+
+/// Fails every request to a host, as if it were down
+struct UnreachableHostApiClient(FakeApiClient, String);
+
+impl ApiClientTrait for UnreachableHostApiClient {
+    fn request<T, U>(&self, server_addr: &KafkaServerAddr, request: Request<T>) -> Result<Response<U>, ApiRequestError>
+    where
+        T: ProtocolSerializable,
+        Vec<u8>: ProtocolDeserializable<Response<U>>,
+    {
+        if server_addr.host == self.1 {
+            Err(ApiRequestError::of(format!("{} is unreachable", self.1)))
+        } else {
+            self.0.request(server_addr, request)
+        }
+    }
+}
+
 fn swap_state(state: &RefCell<State>, event: Event) {
     match event_bus::update_state(event, state.borrow_mut()) {
         Ok(state_result) => state.swap(&RefCell::new(state_result)),
@@ -53,137 +92,216 @@ fn test_api_client_provider(_responses: HashMap<i16, Vec<u8>>) -> ApiClientProvi
     })
 }
 
+fn recording_api_client_provider(
+    _responses: HashMap<i16, Vec<u8>>,
+    requests: Arc<Mutex<Vec<(i16, String)>>>,
+) -> ApiClientProvider<RecordingApiClient> {
+    Box::new(move || {
+        let _responses = _responses.clone();
+        let requests = requests.clone();
+        IO::new(Box::new(move || Ok(RecordingApiClient(FakeApiClient(_responses.clone()), requests.clone()))))
+    })
+}
+
+fn unreachable_host_api_client_provider(_responses: HashMap<i16, Vec<u8>>, host: &str) -> ApiClientProvider<UnreachableHostApiClient> {
+    let host = String::from(host);
+    Box::new(move || {
+        let _responses = _responses.clone();
+        let host = host.clone();
+        IO::new(Box::new(move || Ok(UnreachableHostApiClient(FakeApiClient(_responses.clone()), host.clone()))))
+    })
+}
+
+/// The sorted servers that requests of an ApiKey were sent to
+fn request_servers(requests: &Arc<Mutex<Vec<(i16, String)>>>, api_key: i16) -> Vec<String> {
+    let mut servers = requests.lock().unwrap().iter().filter(|(key, _)| *key == api_key).map(|(_, server)| server.clone()).collect::<Vec<String>>();
+    servers.sort();
+    servers
+}
+
+/// Metadata of brokers 1000 (the controller) and 1001, and of topics '__confluent.support.metrics', 'foo' and 'bar'. The
+/// 16 partitions of 'foo' and 'bar' are led by 1000 (even) and 1001 (odd)
+fn three_topics_metadata() -> Vec<u8> {
+    vec![
+        0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x09, 0x6C, 0x6F, 0x63, 0x61, 0x6C,
+        0x68, 0x6F, 0x73, 0x74, 0x00, 0x00, 0x23, 0x85, 0xFF, 0xFF, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x09, 0x6C, 0x6F, 0x63, 0x61, 0x6C, 0x68, 0x6F,
+        0x73, 0x74, 0x00, 0x00, 0x23, 0x84, 0xFF, 0xFF, 0x00, 0x16, 0x55, 0x37, 0x7A, 0x53, 0x31, 0x4A, 0x51, 0x6D, 0x51, 0x70, 0x6D, 0x66, 0x65,
+        0x6C, 0x6F, 0x5F, 0x63, 0x4E, 0x6D, 0x4E, 0x77, 0x51, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x1B, 0x5F, 0x5F,
+        0x63, 0x6F, 0x6E, 0x66, 0x6C, 0x75, 0x65, 0x6E, 0x74, 0x2E, 0x73, 0x75, 0x70, 0x70, 0x6F, 0x72, 0x74, 0x2E, 0x6D, 0x65, 0x74, 0x72, 0x69,
+        0x63, 0x73, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00,
+        0x03, 0xE9, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x03, 0x66, 0x6F, 0x6F, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00,
+        0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x0B, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01,
+        0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0E, 0x00,
+        0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00,
+        0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0D, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+        0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x03,
+        0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03,
+        0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8,
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x03, 0xE9, 0x00,
+        0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x03, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00,
+        0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0F,
+        0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01,
+        0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x62, 0x61, 0x72, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x08, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0B, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00,
+        0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00,
+        0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x05, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0E, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0D, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01,
+        0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00,
+        0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00,
+        0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+        0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x03,
+        0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03,
+        0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8,
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x03, 0xE9, 0x00,
+        0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x0F, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00,
+        0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00,
+    ]
+}
+
+/// The 25 topic configs of 'bar'
+fn bar_topic_config() -> Vec<u8> {
+    vec![
+        0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0xFF, 0xFF, 0x02, 0x00, 0x03, 0x62, 0x61, 0x72, 0x00,
+        0x00, 0x00, 0x19, 0x00, 0x10, 0x63, 0x6F, 0x6D, 0x70, 0x72, 0x65, 0x73, 0x73, 0x69, 0x6F, 0x6E, 0x2E, 0x74, 0x79, 0x70, 0x65, 0x00, 0x08,
+        0x70, 0x72, 0x6F, 0x64, 0x75, 0x63, 0x65, 0x72, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x25, 0x6C, 0x65, 0x61, 0x64, 0x65, 0x72,
+        0x2E, 0x72, 0x65, 0x70, 0x6C, 0x69, 0x63, 0x61, 0x74, 0x69, 0x6F, 0x6E, 0x2E, 0x74, 0x68, 0x72, 0x6F, 0x74, 0x74, 0x6C, 0x65, 0x64, 0x2E,
+        0x72, 0x65, 0x70, 0x6C, 0x69, 0x63, 0x61, 0x73, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1D, 0x6D, 0x65, 0x73, 0x73,
+        0x61, 0x67, 0x65, 0x2E, 0x64, 0x6F, 0x77, 0x6E, 0x63, 0x6F, 0x6E, 0x76, 0x65, 0x72, 0x73, 0x69, 0x6F, 0x6E, 0x2E, 0x65, 0x6E, 0x61, 0x62,
+        0x6C, 0x65, 0x00, 0x04, 0x74, 0x72, 0x75, 0x65, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x13, 0x6D, 0x69, 0x6E, 0x2E, 0x69, 0x6E,
+        0x73, 0x79, 0x6E, 0x63, 0x2E, 0x72, 0x65, 0x70, 0x6C, 0x69, 0x63, 0x61, 0x73, 0x00, 0x01, 0x31, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x11, 0x73, 0x65, 0x67, 0x6D, 0x65, 0x6E, 0x74, 0x2E, 0x6A, 0x69, 0x74, 0x74, 0x65, 0x72, 0x2E, 0x6D, 0x73, 0x00, 0x01, 0x30, 0x00,
+        0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0E, 0x63, 0x6C, 0x65, 0x61, 0x6E, 0x75, 0x70, 0x2E, 0x70, 0x6F, 0x6C, 0x69, 0x63, 0x79, 0x00,
+        0x06, 0x64, 0x65, 0x6C, 0x65, 0x74, 0x65, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x66, 0x6C, 0x75, 0x73, 0x68, 0x2E, 0x6D,
+        0x73, 0x00, 0x13, 0x39, 0x32, 0x32, 0x33, 0x33, 0x37, 0x32, 0x30, 0x33, 0x36, 0x38, 0x35, 0x34, 0x37, 0x37, 0x35, 0x38, 0x30, 0x37, 0x00,
+        0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x27, 0x66, 0x6F, 0x6C, 0x6C, 0x6F, 0x77, 0x65, 0x72, 0x2E, 0x72, 0x65, 0x70, 0x6C, 0x69, 0x63,
+        0x61, 0x74, 0x69, 0x6F, 0x6E, 0x2E, 0x74, 0x68, 0x72, 0x6F, 0x74, 0x74, 0x6C, 0x65, 0x64, 0x2E, 0x72, 0x65, 0x70, 0x6C, 0x69, 0x63, 0x61,
+        0x73, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0D, 0x73, 0x65, 0x67, 0x6D, 0x65, 0x6E, 0x74, 0x2E, 0x62, 0x79, 0x74,
+        0x65, 0x73, 0x00, 0x0A, 0x31, 0x30, 0x37, 0x33, 0x37, 0x34, 0x31, 0x38, 0x32, 0x34, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0C,
+        0x72, 0x65, 0x74, 0x65, 0x6E, 0x74, 0x69, 0x6F, 0x6E, 0x2E, 0x6D, 0x73, 0x00, 0x09, 0x36, 0x30, 0x34, 0x38, 0x30, 0x30, 0x30, 0x30, 0x30,
+        0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0E, 0x66, 0x6C, 0x75, 0x73, 0x68, 0x2E, 0x6D, 0x65, 0x73, 0x73, 0x61, 0x67, 0x65, 0x73,
+        0x00, 0x13, 0x39, 0x32, 0x32, 0x33, 0x33, 0x37, 0x32, 0x30, 0x33, 0x36, 0x38, 0x35, 0x34, 0x37, 0x37, 0x35, 0x38, 0x30, 0x37, 0x00, 0x05,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x16, 0x6D, 0x65, 0x73, 0x73, 0x61, 0x67, 0x65, 0x2E, 0x66, 0x6F, 0x72, 0x6D, 0x61, 0x74, 0x2E, 0x76,
+        0x65, 0x72, 0x73, 0x69, 0x6F, 0x6E, 0x00, 0x07, 0x32, 0x2E, 0x30, 0x2D, 0x49, 0x56, 0x31, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x14, 0x66, 0x69, 0x6C, 0x65, 0x2E, 0x64, 0x65, 0x6C, 0x65, 0x74, 0x65, 0x2E, 0x64, 0x65, 0x6C, 0x61, 0x79, 0x2E, 0x6D, 0x73, 0x00, 0x05,
+        0x36, 0x30, 0x30, 0x30, 0x30, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x11, 0x6D, 0x61, 0x78, 0x2E, 0x6D, 0x65, 0x73, 0x73, 0x61,
+        0x67, 0x65, 0x2E, 0x62, 0x79, 0x74, 0x65, 0x73, 0x00, 0x07, 0x31, 0x30, 0x30, 0x30, 0x30, 0x31, 0x32, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x15, 0x6D, 0x69, 0x6E, 0x2E, 0x63, 0x6F, 0x6D, 0x70, 0x61, 0x63, 0x74, 0x69, 0x6F, 0x6E, 0x2E, 0x6C, 0x61, 0x67, 0x2E, 0x6D,
+        0x73, 0x00, 0x01, 0x30, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x16, 0x6D, 0x65, 0x73, 0x73, 0x61, 0x67, 0x65, 0x2E, 0x74, 0x69,
+        0x6D, 0x65, 0x73, 0x74, 0x61, 0x6D, 0x70, 0x2E, 0x74, 0x79, 0x70, 0x65, 0x00, 0x0A, 0x43, 0x72, 0x65, 0x61, 0x74, 0x65, 0x54, 0x69, 0x6D,
+        0x65, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0B, 0x70, 0x72, 0x65, 0x61, 0x6C, 0x6C, 0x6F, 0x63, 0x61, 0x74, 0x65, 0x00, 0x05,
+        0x66, 0x61, 0x6C, 0x73, 0x65, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x19, 0x6D, 0x69, 0x6E, 0x2E, 0x63, 0x6C, 0x65, 0x61, 0x6E,
+        0x61, 0x62, 0x6C, 0x65, 0x2E, 0x64, 0x69, 0x72, 0x74, 0x79, 0x2E, 0x72, 0x61, 0x74, 0x69, 0x6F, 0x00, 0x03, 0x30, 0x2E, 0x35, 0x00, 0x05,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x14, 0x69, 0x6E, 0x64, 0x65, 0x78, 0x2E, 0x69, 0x6E, 0x74, 0x65, 0x72, 0x76, 0x61, 0x6C, 0x2E, 0x62,
+        0x79, 0x74, 0x65, 0x73, 0x00, 0x04, 0x34, 0x30, 0x39, 0x36, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1E, 0x75, 0x6E, 0x63, 0x6C,
+        0x65, 0x61, 0x6E, 0x2E, 0x6C, 0x65, 0x61, 0x64, 0x65, 0x72, 0x2E, 0x65, 0x6C, 0x65, 0x63, 0x74, 0x69, 0x6F, 0x6E, 0x2E, 0x65, 0x6E, 0x61,
+        0x62, 0x6C, 0x65, 0x00, 0x05, 0x66, 0x61, 0x6C, 0x73, 0x65, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0F, 0x72, 0x65, 0x74, 0x65,
+        0x6E, 0x74, 0x69, 0x6F, 0x6E, 0x2E, 0x62, 0x79, 0x74, 0x65, 0x73, 0x00, 0x02, 0x2D, 0x31, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x13, 0x64, 0x65, 0x6C, 0x65, 0x74, 0x65, 0x2E, 0x72, 0x65, 0x74, 0x65, 0x6E, 0x74, 0x69, 0x6F, 0x6E, 0x2E, 0x6D, 0x73, 0x00, 0x08, 0x38,
+        0x36, 0x34, 0x30, 0x30, 0x30, 0x30, 0x30, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x73, 0x65, 0x67, 0x6D, 0x65, 0x6E, 0x74,
+        0x2E, 0x6D, 0x73, 0x00, 0x09, 0x36, 0x30, 0x34, 0x38, 0x30, 0x30, 0x30, 0x30, 0x30, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x23,
+        0x6D, 0x65, 0x73, 0x73, 0x61, 0x67, 0x65, 0x2E, 0x74, 0x69, 0x6D, 0x65, 0x73, 0x74, 0x61, 0x6D, 0x70, 0x2E, 0x64, 0x69, 0x66, 0x66, 0x65,
+        0x72, 0x65, 0x6E, 0x63, 0x65, 0x2E, 0x6D, 0x61, 0x78, 0x2E, 0x6D, 0x73, 0x00, 0x13, 0x39, 0x32, 0x32, 0x33, 0x33, 0x37, 0x32, 0x30, 0x33,
+        0x36, 0x38, 0x35, 0x34, 0x37, 0x37, 0x35, 0x38, 0x30, 0x37, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x13, 0x73, 0x65, 0x67, 0x6D,
+        0x65, 0x6E, 0x74, 0x2E, 0x69, 0x6E, 0x64, 0x65, 0x78, 0x2E, 0x62, 0x79, 0x74, 0x65, 0x73, 0x00, 0x08, 0x31, 0x30, 0x34, 0x38, 0x35, 0x37,
+        0x36, 0x30, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ]
+}
+
+/// Offsets of 0 for the partitions of 'foo' led by broker 1001
+fn foo_partition_offsets() -> Vec<u8> {
+    vec![
+        0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x03, 0x66, 0x6F, 0x6F, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00,
+        0x00, 0x01, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x03, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05,
+        0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x00,
+        0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00,
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0B, 0x00, 0x00, 0xFF,
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0D, 0x00, 0x00, 0xFF, 0xFF,
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0F, 0x00, 0x00, 0xFF, 0xFF, 0xFF,
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ]
+}
+
+/// min.insync.replicas of 1 for the topics of `three_topics_metadata`
+fn three_topics_min_isr() -> Vec<u8> {
+    vec![
+        0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0xFF, 0xFF, 0x02, 0x00, 0x1B, 0x5F, 0x5F, 0x63, 0x6F,
+        0x6E, 0x66, 0x6C, 0x75, 0x65, 0x6E, 0x74, 0x2E, 0x73, 0x75, 0x70, 0x70, 0x6F, 0x72, 0x74, 0x2E, 0x6D, 0x65, 0x74, 0x72, 0x69, 0x63, 0x73,
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x13, 0x6D, 0x69, 0x6E, 0x2E, 0x69, 0x6E, 0x73, 0x79, 0x6E, 0x63, 0x2E, 0x72, 0x65, 0x70, 0x6C, 0x69, 0x63,
+        0x61, 0x73, 0x00, 0x01, 0x31, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0x02, 0x00, 0x03, 0x66, 0x6F, 0x6F, 0x00,
+        0x00, 0x00, 0x01, 0x00, 0x13, 0x6D, 0x69, 0x6E, 0x2E, 0x69, 0x6E, 0x73, 0x79, 0x6E, 0x63, 0x2E, 0x72, 0x65, 0x70, 0x6C, 0x69, 0x63, 0x61,
+        0x73, 0x00, 0x01, 0x31, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0x02, 0x00, 0x03, 0x62, 0x61, 0x72, 0x00, 0x00,
+        0x00, 0x01, 0x00, 0x13, 0x6D, 0x69, 0x6E, 0x2E, 0x69, 0x6E, 0x73, 0x79, 0x6E, 0x63, 0x2E, 0x72, 0x65, 0x70, 0x6C, 0x69, 0x63, 0x61, 0x73,
+        0x00, 0x01, 0x31, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ]
+}
+
+/// Metadata of brokers 1000 (the controller) and 1001, and of '__confluent.support.metrics', whose single partition is
+/// led by 1000 and not in sync on 1001
+fn two_brokers_metadata() -> Vec<u8> {
+    vec![
+        0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x09, 0x6C, 0x6F, 0x63, 0x61, 0x6C,
+        0x68, 0x6F, 0x73, 0x74, 0x00, 0x00, 0x23, 0x85, 0xFF, 0xFF, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x09, 0x6C, 0x6F, 0x63, 0x61, 0x6C, 0x68, 0x6F,
+        0x73, 0x74, 0x00, 0x00, 0x23, 0x84, 0xFF, 0xFF, 0x00, 0x16, 0x38, 0x46, 0x31, 0x47, 0x53, 0x56, 0x78, 0x37, 0x51, 0x5F, 0x65, 0x44, 0x6D,
+        0x6B, 0x46, 0x77, 0x53, 0x39, 0x64, 0x59, 0x32, 0x41, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x1B, 0x5F, 0x5F,
+        0x63, 0x6F, 0x6E, 0x66, 0x6C, 0x75, 0x65, 0x6E, 0x74, 0x2E, 0x73, 0x75, 0x70, 0x70, 0x6F, 0x72, 0x74, 0x2E, 0x6D, 0x65, 0x74, 0x72, 0x69,
+        0x63, 0x73, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00,
+        0x03, 0xE8, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00,
+    ]
+}
+
+/// min.insync.replicas of 2 for '__confluent.support.metrics'
+fn support_metrics_min_isr() -> Vec<u8> {
+    vec![
+        0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0xFF, 0xFF, 0x02, 0x00, 0x1B, 0x5F, 0x5F, 0x63, 0x6F,
+        0x6E, 0x66, 0x6C, 0x75, 0x65, 0x6E, 0x74, 0x2E, 0x73, 0x75, 0x70, 0x70, 0x6F, 0x72, 0x74, 0x2E, 0x6D, 0x65, 0x74, 0x72, 0x69, 0x63, 0x73,
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x13, 0x6D, 0x69, 0x6E, 0x2E, 0x69, 0x6E, 0x73, 0x79, 0x6E, 0x63, 0x2E, 0x72, 0x65, 0x70, 0x6C, 0x69, 0x63,
+        0x61, 0x73, 0x00, 0x01, 0x32, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ]
+}
+
 #[test]
 fn get_metadata_and_select_topics() {
     let state = RefCell::new(State::new());
 
     let mut responses: HashMap<i16, Vec<u8>> = HashMap::new();
     // metadata
-    responses.insert(
-        3,
-        vec![
-            0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x09, 0x6C, 0x6F, 0x63, 0x61, 0x6C,
-            0x68, 0x6F, 0x73, 0x74, 0x00, 0x00, 0x23, 0x85, 0xFF, 0xFF, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x09, 0x6C, 0x6F, 0x63, 0x61, 0x6C, 0x68, 0x6F,
-            0x73, 0x74, 0x00, 0x00, 0x23, 0x84, 0xFF, 0xFF, 0x00, 0x16, 0x55, 0x37, 0x7A, 0x53, 0x31, 0x4A, 0x51, 0x6D, 0x51, 0x70, 0x6D, 0x66, 0x65,
-            0x6C, 0x6F, 0x5F, 0x63, 0x4E, 0x6D, 0x4E, 0x77, 0x51, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x1B, 0x5F, 0x5F,
-            0x63, 0x6F, 0x6E, 0x66, 0x6C, 0x75, 0x65, 0x6E, 0x74, 0x2E, 0x73, 0x75, 0x70, 0x70, 0x6F, 0x72, 0x74, 0x2E, 0x6D, 0x65, 0x74, 0x72, 0x69,
-            0x63, 0x73, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00,
-            0x03, 0xE9, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x03, 0x66, 0x6F, 0x6F, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00,
-            0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x0B, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00,
-            0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01,
-            0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0E, 0x00,
-            0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00,
-            0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0D, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
-            0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x03,
-            0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03,
-            0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8,
-            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x03, 0xE9, 0x00,
-            0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x03, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00,
-            0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00,
-            0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0F,
-            0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01,
-            0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x62, 0x61, 0x72, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x08, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0B, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00,
-            0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00,
-            0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x05, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0E, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00,
-            0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0D, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01,
-            0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00,
-            0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00,
-            0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
-            0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x03,
-            0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03,
-            0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8,
-            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x03, 0xE9, 0x00,
-            0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x0F, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00,
-            0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00,
-            0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00,
-        ],
-    );
+    responses.insert(3, three_topics_metadata());
     // topic config for 'bar'
-    responses.insert(
-        32,
-        vec![
-            0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0xFF, 0xFF, 0x02, 0x00, 0x03, 0x62, 0x61, 0x72, 0x00,
-            0x00, 0x00, 0x19, 0x00, 0x10, 0x63, 0x6F, 0x6D, 0x70, 0x72, 0x65, 0x73, 0x73, 0x69, 0x6F, 0x6E, 0x2E, 0x74, 0x79, 0x70, 0x65, 0x00, 0x08,
-            0x70, 0x72, 0x6F, 0x64, 0x75, 0x63, 0x65, 0x72, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x25, 0x6C, 0x65, 0x61, 0x64, 0x65, 0x72,
-            0x2E, 0x72, 0x65, 0x70, 0x6C, 0x69, 0x63, 0x61, 0x74, 0x69, 0x6F, 0x6E, 0x2E, 0x74, 0x68, 0x72, 0x6F, 0x74, 0x74, 0x6C, 0x65, 0x64, 0x2E,
-            0x72, 0x65, 0x70, 0x6C, 0x69, 0x63, 0x61, 0x73, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1D, 0x6D, 0x65, 0x73, 0x73,
-            0x61, 0x67, 0x65, 0x2E, 0x64, 0x6F, 0x77, 0x6E, 0x63, 0x6F, 0x6E, 0x76, 0x65, 0x72, 0x73, 0x69, 0x6F, 0x6E, 0x2E, 0x65, 0x6E, 0x61, 0x62,
-            0x6C, 0x65, 0x00, 0x04, 0x74, 0x72, 0x75, 0x65, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x13, 0x6D, 0x69, 0x6E, 0x2E, 0x69, 0x6E,
-            0x73, 0x79, 0x6E, 0x63, 0x2E, 0x72, 0x65, 0x70, 0x6C, 0x69, 0x63, 0x61, 0x73, 0x00, 0x01, 0x31, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x11, 0x73, 0x65, 0x67, 0x6D, 0x65, 0x6E, 0x74, 0x2E, 0x6A, 0x69, 0x74, 0x74, 0x65, 0x72, 0x2E, 0x6D, 0x73, 0x00, 0x01, 0x30, 0x00,
-            0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0E, 0x63, 0x6C, 0x65, 0x61, 0x6E, 0x75, 0x70, 0x2E, 0x70, 0x6F, 0x6C, 0x69, 0x63, 0x79, 0x00,
-            0x06, 0x64, 0x65, 0x6C, 0x65, 0x74, 0x65, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x66, 0x6C, 0x75, 0x73, 0x68, 0x2E, 0x6D,
-            0x73, 0x00, 0x13, 0x39, 0x32, 0x32, 0x33, 0x33, 0x37, 0x32, 0x30, 0x33, 0x36, 0x38, 0x35, 0x34, 0x37, 0x37, 0x35, 0x38, 0x30, 0x37, 0x00,
-            0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x27, 0x66, 0x6F, 0x6C, 0x6C, 0x6F, 0x77, 0x65, 0x72, 0x2E, 0x72, 0x65, 0x70, 0x6C, 0x69, 0x63,
-            0x61, 0x74, 0x69, 0x6F, 0x6E, 0x2E, 0x74, 0x68, 0x72, 0x6F, 0x74, 0x74, 0x6C, 0x65, 0x64, 0x2E, 0x72, 0x65, 0x70, 0x6C, 0x69, 0x63, 0x61,
-            0x73, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0D, 0x73, 0x65, 0x67, 0x6D, 0x65, 0x6E, 0x74, 0x2E, 0x62, 0x79, 0x74,
-            0x65, 0x73, 0x00, 0x0A, 0x31, 0x30, 0x37, 0x33, 0x37, 0x34, 0x31, 0x38, 0x32, 0x34, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0C,
-            0x72, 0x65, 0x74, 0x65, 0x6E, 0x74, 0x69, 0x6F, 0x6E, 0x2E, 0x6D, 0x73, 0x00, 0x09, 0x36, 0x30, 0x34, 0x38, 0x30, 0x30, 0x30, 0x30, 0x30,
-            0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0E, 0x66, 0x6C, 0x75, 0x73, 0x68, 0x2E, 0x6D, 0x65, 0x73, 0x73, 0x61, 0x67, 0x65, 0x73,
-            0x00, 0x13, 0x39, 0x32, 0x32, 0x33, 0x33, 0x37, 0x32, 0x30, 0x33, 0x36, 0x38, 0x35, 0x34, 0x37, 0x37, 0x35, 0x38, 0x30, 0x37, 0x00, 0x05,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x16, 0x6D, 0x65, 0x73, 0x73, 0x61, 0x67, 0x65, 0x2E, 0x66, 0x6F, 0x72, 0x6D, 0x61, 0x74, 0x2E, 0x76,
-            0x65, 0x72, 0x73, 0x69, 0x6F, 0x6E, 0x00, 0x07, 0x32, 0x2E, 0x30, 0x2D, 0x49, 0x56, 0x31, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x14, 0x66, 0x69, 0x6C, 0x65, 0x2E, 0x64, 0x65, 0x6C, 0x65, 0x74, 0x65, 0x2E, 0x64, 0x65, 0x6C, 0x61, 0x79, 0x2E, 0x6D, 0x73, 0x00, 0x05,
-            0x36, 0x30, 0x30, 0x30, 0x30, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x11, 0x6D, 0x61, 0x78, 0x2E, 0x6D, 0x65, 0x73, 0x73, 0x61,
-            0x67, 0x65, 0x2E, 0x62, 0x79, 0x74, 0x65, 0x73, 0x00, 0x07, 0x31, 0x30, 0x30, 0x30, 0x30, 0x31, 0x32, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x15, 0x6D, 0x69, 0x6E, 0x2E, 0x63, 0x6F, 0x6D, 0x70, 0x61, 0x63, 0x74, 0x69, 0x6F, 0x6E, 0x2E, 0x6C, 0x61, 0x67, 0x2E, 0x6D,
-            0x73, 0x00, 0x01, 0x30, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x16, 0x6D, 0x65, 0x73, 0x73, 0x61, 0x67, 0x65, 0x2E, 0x74, 0x69,
-            0x6D, 0x65, 0x73, 0x74, 0x61, 0x6D, 0x70, 0x2E, 0x74, 0x79, 0x70, 0x65, 0x00, 0x0A, 0x43, 0x72, 0x65, 0x61, 0x74, 0x65, 0x54, 0x69, 0x6D,
-            0x65, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0B, 0x70, 0x72, 0x65, 0x61, 0x6C, 0x6C, 0x6F, 0x63, 0x61, 0x74, 0x65, 0x00, 0x05,
-            0x66, 0x61, 0x6C, 0x73, 0x65, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x19, 0x6D, 0x69, 0x6E, 0x2E, 0x63, 0x6C, 0x65, 0x61, 0x6E,
-            0x61, 0x62, 0x6C, 0x65, 0x2E, 0x64, 0x69, 0x72, 0x74, 0x79, 0x2E, 0x72, 0x61, 0x74, 0x69, 0x6F, 0x00, 0x03, 0x30, 0x2E, 0x35, 0x00, 0x05,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x14, 0x69, 0x6E, 0x64, 0x65, 0x78, 0x2E, 0x69, 0x6E, 0x74, 0x65, 0x72, 0x76, 0x61, 0x6C, 0x2E, 0x62,
-            0x79, 0x74, 0x65, 0x73, 0x00, 0x04, 0x34, 0x30, 0x39, 0x36, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1E, 0x75, 0x6E, 0x63, 0x6C,
-            0x65, 0x61, 0x6E, 0x2E, 0x6C, 0x65, 0x61, 0x64, 0x65, 0x72, 0x2E, 0x65, 0x6C, 0x65, 0x63, 0x74, 0x69, 0x6F, 0x6E, 0x2E, 0x65, 0x6E, 0x61,
-            0x62, 0x6C, 0x65, 0x00, 0x05, 0x66, 0x61, 0x6C, 0x73, 0x65, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0F, 0x72, 0x65, 0x74, 0x65,
-            0x6E, 0x74, 0x69, 0x6F, 0x6E, 0x2E, 0x62, 0x79, 0x74, 0x65, 0x73, 0x00, 0x02, 0x2D, 0x31, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x13, 0x64, 0x65, 0x6C, 0x65, 0x74, 0x65, 0x2E, 0x72, 0x65, 0x74, 0x65, 0x6E, 0x74, 0x69, 0x6F, 0x6E, 0x2E, 0x6D, 0x73, 0x00, 0x08, 0x38,
-            0x36, 0x34, 0x30, 0x30, 0x30, 0x30, 0x30, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x73, 0x65, 0x67, 0x6D, 0x65, 0x6E, 0x74,
-            0x2E, 0x6D, 0x73, 0x00, 0x09, 0x36, 0x30, 0x34, 0x38, 0x30, 0x30, 0x30, 0x30, 0x30, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x23,
-            0x6D, 0x65, 0x73, 0x73, 0x61, 0x67, 0x65, 0x2E, 0x74, 0x69, 0x6D, 0x65, 0x73, 0x74, 0x61, 0x6D, 0x70, 0x2E, 0x64, 0x69, 0x66, 0x66, 0x65,
-            0x72, 0x65, 0x6E, 0x63, 0x65, 0x2E, 0x6D, 0x61, 0x78, 0x2E, 0x6D, 0x73, 0x00, 0x13, 0x39, 0x32, 0x32, 0x33, 0x33, 0x37, 0x32, 0x30, 0x33,
-            0x36, 0x38, 0x35, 0x34, 0x37, 0x37, 0x35, 0x38, 0x30, 0x37, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x13, 0x73, 0x65, 0x67, 0x6D,
-            0x65, 0x6E, 0x74, 0x2E, 0x69, 0x6E, 0x64, 0x65, 0x78, 0x2E, 0x62, 0x79, 0x74, 0x65, 0x73, 0x00, 0x08, 0x31, 0x30, 0x34, 0x38, 0x35, 0x37,
-            0x36, 0x30, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00,
-        ],
-    );
+    responses.insert(32, bar_topic_config());
     // topic partitions for 'foo'
-    responses.insert(
-        2,
-        vec![
-            0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x03, 0x66, 0x6F, 0x6F, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00,
-            0x00, 0x01, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x03, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05,
-            0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x00,
-            0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00,
-            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0B, 0x00, 0x00, 0xFF,
-            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0D, 0x00, 0x00, 0xFF, 0xFF,
-            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0F, 0x00, 0x00, 0xFF, 0xFF, 0xFF,
-            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        ],
-    );
+    responses.insert(2, foo_partition_offsets());
 
     /* Get metadata */
     let metadata_retrieved_event =
         match event_bus::to_event(Message::GetMetadata(test_bootstrap_server(), None), test_api_client_provider(responses.clone())) {
             Event::MetadataRetrieved(statefn) => match statefn(&state.borrow_mut()) {
-                Ok(MetadataPayload::Metadata(metadata_response)) => {
+                Ok((
+                    _,
+                    MetadataPayload::TopicsMetadata(metadata_response, topic_consumer_lag, topic_retained_messages, _, _, offset_snapshots, warnings),
+                )) => {
+                    assert_eq!(offset_snapshots.keys().collect::<Vec<&String>>(), vec!["foo"]);
+                    assert_eq!(topic_retained_messages.get("foo"), Some(&0));
+                    assert!(topic_consumer_lag.is_empty());
+                    assert!(warnings.is_empty());
                     assert_eq!(metadata_response.topic_metadata.len(), 3);
                     assert_eq!(
                         metadata_response.topic_metadata.iter().map(|t| t.topic.as_str()).collect::<Vec<&str>>(),
@@ -191,12 +309,13 @@ fn get_metadata_and_select_topics() {
                     );
                     Event::MetadataRetrieved(statefn)
                 }
-                _ => panic!("Expected MetadataPayload::Metadata"),
+                _ => panic!("Expected MetadataPayload::TopicsMetadata"),
             },
             _ => panic!("Expected Event::MetadataRetrieved"),
         };
     swap_state(&state, metadata_retrieved_event);
     let updated_state = state.borrow().clone();
+    assert!(updated_state.live_server.is_some());
 
     /* Select the second topic */
     let selection_updated = match event_bus::to_event(Message::Select(MoveSelection::Down), empty_api_client_provider()) {
@@ -246,6 +365,23 @@ fn get_metadata_and_select_topics() {
     let partition_info_state = updated_state.partition_info_state.expect("Should have selected partitions for topic");
     assert_eq!(updated_state.selected_index, 2);
     assert_eq!(partition_info_state.partition_metadata.len(), 16);
+    assert_eq!(partition_info_state.log_start_offsets.len(), 8);
+    assert!(partition_info_state.timestamp_offsets.is_none());
+
+    /* Look up the partition offsets at a time */
+    let timestamp = utils::parse_timestamp("2019-03-01T09:00:00+01:00").expect("Should parse ISO-8601 datetime");
+    assert_eq!(timestamp, 1_551_427_200_000);
+    let offsets_looked_up =
+        event_bus::to_event(Message::LookupOffsets(test_bootstrap_server(), timestamp), test_api_client_provider(responses.clone()));
+    swap_state(&state, offsets_looked_up);
+    let updated_state = state.borrow().clone();
+    let timestamp_offsets = updated_state.partition_info_state.and_then(|p| p.timestamp_offsets).expect("Should have looked up offsets");
+    assert_eq!(timestamp_offsets.timestamp, timestamp);
+    assert_eq!(timestamp_offsets.offsets.len(), 8);
+    match updated_state.dialog_message {
+        Some(DialogMessage::Info(info)) => assert_eq!(info, "AT: offsets at 2019-03-01T08:00:00.000Z".to_string()),
+        _ => panic!("Expected DialogMessage::Info"),
+    }
 }
 
 #[test]
@@ -254,64 +390,15 @@ fn topic_deletion_marking() {
 
     let mut responses: HashMap<i16, Vec<u8>> = HashMap::new();
     // metadata
-    responses.insert(
-        3,
-        vec![
-            0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x09, 0x6C, 0x6F, 0x63, 0x61, 0x6C,
-            0x68, 0x6F, 0x73, 0x74, 0x00, 0x00, 0x23, 0x85, 0xFF, 0xFF, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x09, 0x6C, 0x6F, 0x63, 0x61, 0x6C, 0x68, 0x6F,
-            0x73, 0x74, 0x00, 0x00, 0x23, 0x84, 0xFF, 0xFF, 0x00, 0x16, 0x55, 0x37, 0x7A, 0x53, 0x31, 0x4A, 0x51, 0x6D, 0x51, 0x70, 0x6D, 0x66, 0x65,
-            0x6C, 0x6F, 0x5F, 0x63, 0x4E, 0x6D, 0x4E, 0x77, 0x51, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x1B, 0x5F, 0x5F,
-            0x63, 0x6F, 0x6E, 0x66, 0x6C, 0x75, 0x65, 0x6E, 0x74, 0x2E, 0x73, 0x75, 0x70, 0x70, 0x6F, 0x72, 0x74, 0x2E, 0x6D, 0x65, 0x74, 0x72, 0x69,
-            0x63, 0x73, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00,
-            0x03, 0xE9, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x03, 0x66, 0x6F, 0x6F, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00,
-            0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x0B, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00,
-            0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01,
-            0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0E, 0x00,
-            0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00,
-            0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0D, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
-            0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x03,
-            0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03,
-            0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8,
-            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x03, 0xE9, 0x00,
-            0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x03, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00,
-            0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00,
-            0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0F,
-            0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01,
-            0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x62, 0x61, 0x72, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x08, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0B, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00,
-            0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00,
-            0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x05, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0E, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00,
-            0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0D, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01,
-            0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00,
-            0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00,
-            0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
-            0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x03,
-            0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03,
-            0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8,
-            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x03, 0xE9, 0x00,
-            0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x0F, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00,
-            0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00,
-            0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00,
-        ],
-    );
+    responses.insert(3, three_topics_metadata());
     // delete 'bar'
     responses.insert(20, vec![0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x03, 0x62, 0x61, 0x72, 0x00, 0x00]);
 
+    // partition offsets
+    responses.insert(2, foo_partition_offsets());
+    // min.insync.replicas of all topics
+    responses.insert(32, three_topics_min_isr());
+
     /* Get metadata */
     let metadata_retrieved_event =
         event_bus::to_event(Message::GetMetadata(test_bootstrap_server(), None), test_api_client_provider(responses.clone()));
@@ -343,61 +430,7 @@ fn modify_topic_config() {
 
     let mut responses = HashMap::new();
     // metadata
-    responses.insert(
-        3,
-        vec![
-            0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x09, 0x6C, 0x6F, 0x63, 0x61, 0x6C,
-            0x68, 0x6F, 0x73, 0x74, 0x00, 0x00, 0x23, 0x85, 0xFF, 0xFF, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x09, 0x6C, 0x6F, 0x63, 0x61, 0x6C, 0x68, 0x6F,
-            0x73, 0x74, 0x00, 0x00, 0x23, 0x84, 0xFF, 0xFF, 0x00, 0x16, 0x55, 0x37, 0x7A, 0x53, 0x31, 0x4A, 0x51, 0x6D, 0x51, 0x70, 0x6D, 0x66, 0x65,
-            0x6C, 0x6F, 0x5F, 0x63, 0x4E, 0x6D, 0x4E, 0x77, 0x51, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x1B, 0x5F, 0x5F,
-            0x63, 0x6F, 0x6E, 0x66, 0x6C, 0x75, 0x65, 0x6E, 0x74, 0x2E, 0x73, 0x75, 0x70, 0x70, 0x6F, 0x72, 0x74, 0x2E, 0x6D, 0x65, 0x74, 0x72, 0x69,
-            0x63, 0x73, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00,
-            0x03, 0xE9, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x03, 0x66, 0x6F, 0x6F, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00,
-            0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x0B, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00,
-            0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01,
-            0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0E, 0x00,
-            0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00,
-            0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0D, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
-            0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x03,
-            0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03,
-            0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8,
-            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x03, 0xE9, 0x00,
-            0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x03, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00,
-            0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00,
-            0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0F,
-            0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01,
-            0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x62, 0x61, 0x72, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x08, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0B, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00,
-            0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00,
-            0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x05, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0E, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00,
-            0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0D, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01,
-            0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00,
-            0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00,
-            0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
-            0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x03,
-            0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03,
-            0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8,
-            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x03, 0xE9, 0x00,
-            0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x0F, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE9, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00,
-            0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00,
-            0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00,
-        ],
-    );
+    responses.insert(3, three_topics_metadata());
     // topic config for 'foo'
     responses.insert(
         32,
@@ -452,6 +485,8 @@ fn modify_topic_config() {
         33,
         vec![0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0xFF, 0xFF, 0x02, 0x00, 0x03, 0x66, 0x6F, 0x6F],
     );
+    // partition offsets
+    responses.insert(2, foo_partition_offsets());
 
     /* Get metadata */
     let metadata_retrieved_event =
@@ -514,20 +549,21 @@ fn create_topic() {
 
     let mut responses = HashMap::new();
     // metadata
+    responses.insert(3, two_brokers_metadata());
+    // create response
+    responses.insert(19, vec![0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x01, 0x00, 0x03, 0x46, 0x6F, 0x6F, 0x00, 0x00, 0xFF, 0xFF]);
+
+    // partition offsets
     responses.insert(
-        3,
+        2,
         vec![
-            0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x09, 0x6C, 0x6F, 0x63, 0x61, 0x6C,
-            0x68, 0x6F, 0x73, 0x74, 0x00, 0x00, 0x23, 0x85, 0xFF, 0xFF, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x09, 0x6C, 0x6F, 0x63, 0x61, 0x6C, 0x68, 0x6F,
-            0x73, 0x74, 0x00, 0x00, 0x23, 0x84, 0xFF, 0xFF, 0x00, 0x16, 0x38, 0x46, 0x31, 0x47, 0x53, 0x56, 0x78, 0x37, 0x51, 0x5F, 0x65, 0x44, 0x6D,
-            0x6B, 0x46, 0x77, 0x53, 0x39, 0x64, 0x59, 0x32, 0x41, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x1B, 0x5F, 0x5F,
-            0x63, 0x6F, 0x6E, 0x66, 0x6C, 0x75, 0x65, 0x6E, 0x74, 0x2E, 0x73, 0x75, 0x70, 0x70, 0x6F, 0x72, 0x74, 0x2E, 0x6D, 0x65, 0x74, 0x72, 0x69,
-            0x63, 0x73, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00,
-            0x03, 0xE8, 0x00, 0x00, 0x03, 0xE9, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x1B, 0x5F, 0x5F, 0x63, 0x6F, 0x6E, 0x66, 0x6C, 0x75, 0x65,
+            0x6E, 0x74, 0x2E, 0x73, 0x75, 0x70, 0x70, 0x6F, 0x72, 0x74, 0x2E, 0x6D, 0x65, 0x74, 0x72, 0x69, 0x63, 0x73, 0x00, 0x00, 0x00, 0x01, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x2A,
         ],
     );
-    // create response
-    responses.insert(19, vec![0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x01, 0x00, 0x03, 0x46, 0x6F, 0x6F, 0x00, 0x00, 0xFF, 0xFF]);
+    // min.insync.replicas of all topics
+    responses.insert(32, support_metrics_min_isr());
 
     /* Get metadata */
     let metadata_retrieved_event =
@@ -565,3 +601,538 @@ fn create_topic() {
         _ => panic!(),
     }
 }
+
+#[test]
+fn select_brokers() {
+    let state = RefCell::new(State::new());
+
+    let mut responses = HashMap::new();
+    // metadata
+    responses.insert(3, two_brokers_metadata());
+
+    /* Show brokers */
+    let view_toggled = event_bus::to_event(Message::ToggleView(CurrentView::Brokers), empty_api_client_provider());
+    swap_state(&state, view_toggled);
+
+    /* Get metadata */
+    let metadata_retrieved_event =
+        event_bus::to_event(Message::GetMetadata(test_bootstrap_server(), None), test_api_client_provider(responses.clone()));
+    swap_state(&state, metadata_retrieved_event);
+    let updated_state = state.borrow().clone();
+    assert_eq!(updated_state.current_view, CurrentView::Brokers);
+    assert_eq!(updated_state.metadata.unwrap().brokers.iter().map(|b| b.node_id).collect::<Vec<i32>>(), vec![1000, 1001]);
+
+    /* Select the second broker */
+    let selection_updated = event_bus::to_event(Message::Select(MoveSelection::Down), empty_api_client_provider());
+    swap_state(&state, selection_updated);
+    let selection_updated = event_bus::to_event(Message::Select(MoveSelection::Down), empty_api_client_provider());
+    swap_state(&state, selection_updated);
+    let updated_state = state.borrow().clone();
+    assert_eq!(updated_state.selected_broker_index, 2);
+    assert!(updated_state.selected_cluster_default());
+    assert!(updated_state.selected_broker_metadata().is_none());
+
+    /* Select back to the second broker, the cluster-wide defaults being the last entry */
+    let selection_updated = event_bus::to_event(Message::Select(MoveSelection::Down), empty_api_client_provider());
+    swap_state(&state, selection_updated);
+    let selection_updated = event_bus::to_event(Message::Select(MoveSelection::Up), empty_api_client_provider());
+    swap_state(&state, selection_updated);
+    let updated_state = state.borrow().clone();
+    assert_eq!(updated_state.selected_broker_index, 1);
+    assert!(!updated_state.selected_cluster_default());
+    assert_eq!(updated_state.selected_broker_metadata().map(|b| b.node_id), Some(1001));
+}
+
+#[test]
+fn modify_broker_configs() {
+    let state = RefCell::new(State::new());
+
+    let mut responses = HashMap::new();
+    // metadata
+    responses.insert(3, two_brokers_metadata());
+    // broker config
+    responses.insert(
+        32,
+        vec![
+            0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0xFF, 0xFF, 0x04, 0x00, 0x04, 0x31, 0x30, 0x30, 0x31,
+            0x00, 0x00, 0x00, 0x03, 0x00, 0x09, 0x62, 0x72, 0x6F, 0x6B, 0x65, 0x72, 0x2E, 0x69, 0x64, 0x00, 0x04, 0x31, 0x30, 0x30, 0x31, 0x01, 0x04,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x13, 0x6C, 0x6F, 0x67, 0x2E, 0x63, 0x6C, 0x65, 0x61, 0x6E, 0x65, 0x72, 0x2E, 0x74, 0x68, 0x72, 0x65,
+            0x61, 0x64, 0x73, 0x00, 0x01, 0x32, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x15, 0x73, 0x73, 0x6C, 0x2E, 0x6B, 0x65, 0x79, 0x73,
+            0x74, 0x6F, 0x72, 0x65, 0x2E, 0x70, 0x61, 0x73, 0x73, 0x77, 0x6F, 0x72, 0x64, 0xFF, 0xFF, 0x00, 0x05, 0x01, 0x00, 0x00, 0x00, 0x00,
+        ],
+    );
+    // alter config
+    responses.insert(
+        33,
+        vec![
+            0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0xFF, 0xFF, 0x04, 0x00, 0x04, 0x31, 0x30, 0x30, 0x31,
+        ],
+    );
+
+    /* Get metadata and show the config of the second broker */
+    let metadata_retrieved_event =
+        event_bus::to_event(Message::GetMetadata(test_bootstrap_server(), None), test_api_client_provider(responses.clone()));
+    swap_state(&state, metadata_retrieved_event);
+    let view_toggled = event_bus::to_event(Message::ToggleView(CurrentView::Brokers), empty_api_client_provider());
+    swap_state(&state, view_toggled);
+    let selection_updated = event_bus::to_event(Message::Select(MoveSelection::Down), empty_api_client_provider());
+    swap_state(&state, selection_updated);
+    let view_toggled = event_bus::to_event(Message::ToggleView(CurrentView::BrokerInfo), empty_api_client_provider());
+    swap_state(&state, view_toggled);
+
+    let requests = Arc::new(Mutex::new(vec![]));
+    let metadata_retrieved_event =
+        event_bus::to_event(Message::GetMetadata(test_bootstrap_server(), None), recording_api_client_provider(responses.clone(), requests.clone()));
+    swap_state(&state, metadata_retrieved_event);
+    assert_eq!(request_servers(&requests, 32), vec!["localhost:9093"]);
+
+    /* The first config ('broker.id') is read-only */
+    let modify_event =
+        event_bus::to_event(Message::ModifyValue(test_bootstrap_server(), Some(String::from("1002"))), test_api_client_provider(responses.clone()));
+    match modify_event {
+        Event::ValueModified(modification) => match modification(&state.borrow()) {
+            Err(StateFNError::Error(err)) => assert_eq!(err, "'broker.id' is read-only and can not be updated dynamically"),
+            _ => panic!("Expected the read-only config to be refused"),
+        },
+        _ => panic!("Expected Event::ValueModified"),
+    }
+
+    /* Modify the second config ('log.cleaner.threads') on the broker itself */
+    let selection_updated = event_bus::to_event(Message::Select(MoveSelection::Down), empty_api_client_provider());
+    swap_state(&state, selection_updated);
+    let requests = Arc::new(Mutex::new(vec![]));
+    let modify_event = match event_bus::to_event(
+        Message::ModifyValue(test_bootstrap_server(), Some(String::from("3"))),
+        recording_api_client_provider(responses.clone(), requests.clone()),
+    ) {
+        Event::ValueModified(modification) => match modification(&state.borrow()) {
+            Ok(Modification::BrokerConfig(config_name)) => {
+                assert_eq!(config_name, "log.cleaner.threads");
+                assert_eq!(request_servers(&requests, 33), vec!["localhost:9093"]);
+                Event::ValueModified(modification)
+            }
+            _ => panic!("Expected modification of broker config"),
+        },
+        _ => panic!("Expected Event::ValueModified"),
+    };
+    swap_state(&state, modify_event);
+    let broker_info_state = state.borrow().clone().broker_info_state.unwrap();
+    assert_eq!(broker_info_state.configs_marked_modified, vec!["log.cleaner.threads"]);
+    let (server, resource) = event_bus::broker_config_resource(&broker_info_state, &test_bootstrap_server(), vec![]);
+    assert_eq!(format!("{}:{}", server.host, server.port), "localhost:9093");
+    assert_eq!(resource.resource_name, "1001");
+
+    /* Changes are refused once a sensitive config is set, since AlterConfigs would reset it */
+    responses.insert(
+        32,
+        vec![
+            0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0xFF, 0xFF, 0x04, 0x00, 0x04, 0x31, 0x30, 0x30, 0x31,
+            0x00, 0x00, 0x00, 0x03, 0x00, 0x09, 0x62, 0x72, 0x6F, 0x6B, 0x65, 0x72, 0x2E, 0x69, 0x64, 0x00, 0x04, 0x31, 0x30, 0x30, 0x31, 0x01, 0x04,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x13, 0x6C, 0x6F, 0x67, 0x2E, 0x63, 0x6C, 0x65, 0x61, 0x6E, 0x65, 0x72, 0x2E, 0x74, 0x68, 0x72, 0x65,
+            0x61, 0x64, 0x73, 0x00, 0x01, 0x32, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x15, 0x73, 0x73, 0x6C, 0x2E, 0x6B, 0x65, 0x79, 0x73,
+            0x74, 0x6F, 0x72, 0x65, 0x2E, 0x70, 0x61, 0x73, 0x73, 0x77, 0x6F, 0x72, 0x64, 0xFF, 0xFF, 0x00, 0x02, 0x01, 0x00, 0x00, 0x00, 0x00,
+        ],
+    );
+    let metadata_retrieved_event =
+        event_bus::to_event(Message::GetMetadata(test_bootstrap_server(), None), test_api_client_provider(responses.clone()));
+    swap_state(&state, metadata_retrieved_event);
+    match event_bus::to_event(Message::Delete(test_bootstrap_server(), 30_000), test_api_client_provider(responses.clone())) {
+        Event::ResourceDeleted(deletion) => match deletion(&state.borrow()) {
+            Err(StateFNError::Error(err)) => assert_eq!(err, "Sensitive config 'ssl.keystore.password' is set and would be reset by this change"),
+            _ => panic!("Expected the change to be refused"),
+        },
+        _ => panic!("Expected Event::ResourceDeleted"),
+    }
+
+    /* Show the cluster-wide defaults */
+    // cluster-wide default and controller configs
+    responses.insert(
+        32,
+        vec![
+            0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0xFF, 0xFF, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+            0x00, 0x13, 0x6C, 0x6F, 0x67, 0x2E, 0x63, 0x6C, 0x65, 0x61, 0x6E, 0x65, 0x72, 0x2E, 0x74, 0x68, 0x72, 0x65, 0x61, 0x64, 0x73, 0x00, 0x01,
+            0x34, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0x04, 0x00, 0x04, 0x31, 0x30, 0x30, 0x30, 0x00, 0x00, 0x00, 0x04,
+            0x00, 0x09, 0x62, 0x72, 0x6F, 0x6B, 0x65, 0x72, 0x2E, 0x69, 0x64, 0x00, 0x04, 0x31, 0x30, 0x30, 0x30, 0x01, 0x04, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x13, 0x6C, 0x6F, 0x67, 0x2E, 0x63, 0x6C, 0x65, 0x61, 0x6E, 0x65, 0x72, 0x2E, 0x74, 0x68, 0x72, 0x65, 0x61, 0x64, 0x73, 0x00,
+            0x01, 0x34, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x6C, 0x6F, 0x67, 0x2E, 0x72, 0x65, 0x74, 0x65, 0x6E, 0x74, 0x69, 0x6F,
+            0x6E, 0x2E, 0x6D, 0x73, 0x00, 0x04, 0x31, 0x30, 0x30, 0x30, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0E, 0x6E, 0x75, 0x6D, 0x2E,
+            0x69, 0x6F, 0x2E, 0x74, 0x68, 0x72, 0x65, 0x61, 0x64, 0x73, 0x00, 0x01, 0x38, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ],
+    );
+    responses.insert(33, vec![0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0xFF, 0xFF, 0x04, 0x00, 0x00]);
+    let view_toggled = event_bus::to_event(Message::ToggleView(CurrentView::Brokers), empty_api_client_provider());
+    swap_state(&state, view_toggled);
+    let selection_updated = event_bus::to_event(Message::Select(MoveSelection::Down), empty_api_client_provider());
+    swap_state(&state, selection_updated);
+    let view_toggled = event_bus::to_event(Message::ToggleView(CurrentView::BrokerInfo), empty_api_client_provider());
+    swap_state(&state, view_toggled);
+    let metadata_retrieved_event =
+        event_bus::to_event(Message::GetMetadata(test_bootstrap_server(), None), test_api_client_provider(responses.clone()));
+    swap_state(&state, metadata_retrieved_event);
+    let broker_info_state = state.borrow().clone().broker_info_state.unwrap();
+    assert!(broker_info_state.broker_metadata.is_none());
+    // read-only configs are left out, and the controller's own dynamic config is not a cluster-wide default
+    assert_eq!(
+        broker_info_state
+            .config_resource
+            .config_entries
+            .iter()
+            .map(|config_entry| (config_entry.config_name.as_str(), config_entry.config_value.as_ref().map(|value| value.as_str())))
+            .collect::<Vec<(&str, Option<&str>)>>(),
+        vec![("log.cleaner.threads", Some("4")), ("log.retention.ms", None), ("num.io.threads", Some("8"))]
+    );
+
+    /* Modify the cluster-wide default of the first config ('log.cleaner.threads') via the bootstrap server */
+    let requests = Arc::new(Mutex::new(vec![]));
+    match event_bus::to_event(
+        Message::ModifyValue(test_bootstrap_server(), Some(String::from("3"))),
+        recording_api_client_provider(responses.clone(), requests.clone()),
+    ) {
+        Event::ValueModified(modification) => match modification(&state.borrow()) {
+            Ok(Modification::BrokerConfig(config_name)) => assert_eq!(config_name, "log.cleaner.threads"),
+            _ => panic!("Expected modification of broker config"),
+        },
+        _ => panic!("Expected Event::ValueModified"),
+    }
+    assert_eq!(request_servers(&requests, 33), vec!["fake:9092"]);
+    let (_, resource) = event_bus::broker_config_resource(&broker_info_state, &test_bootstrap_server(), vec![]);
+    assert_eq!(resource.resource_name, "");
+}
+
+#[test]
+fn unhealthy_partitions() {
+    let state = RefCell::new(State::new());
+
+    let mut responses = HashMap::new();
+    // metadata, with the single partition of '__confluent.support.metrics' not in sync on broker 1001
+    responses.insert(3, two_brokers_metadata());
+    // min.insync.replicas of 2 for '__confluent.support.metrics'
+    responses.insert(32, support_metrics_min_isr());
+
+    /* Show unhealthy partitions */
+    let view_toggled = event_bus::to_event(Message::ToggleView(CurrentView::Health), empty_api_client_provider());
+    swap_state(&state, view_toggled);
+
+    /* Get metadata */
+    let metadata_retrieved_event =
+        event_bus::to_event(Message::GetMetadata(test_bootstrap_server(), None), test_api_client_provider(responses.clone()));
+    swap_state(&state, metadata_retrieved_event);
+    let updated_state = state.borrow().clone();
+    assert_eq!(updated_state.current_view, CurrentView::Health);
+    assert_eq!(updated_state.topic_min_isr.as_ref().and_then(|min_isr| min_isr.get("__confluent.support.metrics")), Some(&2));
+
+    /* A min.insync.replicas of 2 puts the partition under min ISR */
+    let metadata = updated_state.metadata.as_ref().expect("Should have metadata");
+    let summary = health::health_summary(metadata, &updated_state.topic_min_isr);
+    assert_eq!(summary.under_replicated, 1);
+    assert_eq!(summary.offline, 0);
+    assert_eq!(summary.under_min_isr, Some(1));
+    assert_eq!(health::topic_health(&metadata.topic_metadata[0], &updated_state.topic_min_isr), Some(PartitionHealth::UnderMinIsr));
+
+    let unhealthy_partitions = health::unhealthy_partitions(metadata, &updated_state.topic_min_isr);
+    assert_eq!(
+        unhealthy_partitions.iter().map(|(t, p, h)| (t.topic.as_str(), p.partition, *h)).collect::<Vec<(&str, i32, PartitionHealth)>>(),
+        vec![("__confluent.support.metrics", 0, PartitionHealth::UnderMinIsr)]
+    );
+
+    /* Without min.insync.replicas, the partition is only under-replicated */
+    let summary = health::health_summary(metadata, &None);
+    assert_eq!(summary.under_min_isr, None);
+    let unhealthy_partitions = health::unhealthy_partitions(metadata, &None);
+    assert_eq!(
+        unhealthy_partitions.iter().map(|(t, p, h)| (t.topic.as_str(), p.partition, *h)).collect::<Vec<(&str, i32, PartitionHealth)>>(),
+        vec![("__confluent.support.metrics", 0, PartitionHealth::UnderReplicated)]
+    );
+}
+
+#[test]
+fn offset_rates_between_refreshes() {
+    let mut state = State::new();
+
+    let offset_snapshot = |retrieved_ms: u64, consumer_group: Option<&str>, partition_offsets: Vec<(i32, i64)>, consumer_offsets: Vec<(i32, i64)>| {
+        let mut offset_snapshots = HashMap::new();
+        offset_snapshots.insert(
+            String::from("foo"),
+            OffsetSnapshot {
+                retrieved_ms,
+                consumer_group: consumer_group.map(String::from),
+                partition_offsets: partition_offsets.into_iter().collect(),
+                consumer_offsets: consumer_offsets.into_iter().collect(),
+            },
+        );
+        offset_snapshots
+    };
+
+    /* First refresh has nothing to compare against */
+    state.record_offset_snapshots(offset_snapshot(1_000, Some("group"), vec![(0, 100), (1, 50)], vec![(0, 90), (1, -1)]));
+    assert!(state.offset_rates.get("foo").is_none());
+
+    /* Two seconds later */
+    state.record_offset_snapshots(offset_snapshot(3_000, Some("group"), vec![(0, 120), (1, 60)], vec![(0, 110), (1, 10)]));
+    let rates = state.offset_rates.get("foo").expect("Should have rates for topic");
+    assert_eq!(rates.get(&0), Some(&OffsetRates { produced: 10.0, consumed: Some(10.0) }));
+    assert_eq!(rates.get(&1), Some(&OffsetRates { produced: 5.0, consumed: None })); // nothing was committed before
+    assert_eq!(OffsetRates::total(rates), Some(OffsetRates { produced: 15.0, consumed: Some(10.0) }));
+
+    /* Switching consumer groups only keeps the produce rates */
+    state.record_offset_snapshots(offset_snapshot(4_000, Some("other-group"), vec![(0, 130), (1, 60)], vec![(0, 0), (1, 0)]));
+    let rates = state.offset_rates.get("foo").expect("Should have rates for topic");
+    assert_eq!(OffsetRates::total(rates), Some(OffsetRates { produced: 10.0, consumed: None }));
+}
+
+#[test]
+fn refresh_keeps_selection() {
+    let state = RefCell::new(State::new());
+
+    let mut responses: HashMap<i16, Vec<u8>> = HashMap::new();
+    // metadata
+    responses.insert(3, three_topics_metadata());
+    // topic config for 'bar'
+    responses.insert(32, bar_topic_config());
+    // partition offsets
+    responses.insert(2, foo_partition_offsets());
+    let get_metadata = || event_bus::to_event(Message::GetMetadata(test_bootstrap_server(), None), test_api_client_provider(responses.clone()));
+
+    /* Get metadata and select the second topic, 'bar' */
+    swap_state(&state, get_metadata());
+    swap_state(&state, event_bus::to_event(Message::Select(MoveSelection::Down), empty_api_client_provider()));
+
+    /* Select the third config of 'bar' */
+    swap_state(&state, event_bus::to_event(Message::ToggleView(CurrentView::TopicInfo), empty_api_client_provider()));
+    swap_state(&state, get_metadata());
+    swap_state(&state, event_bus::to_event(Message::Select(MoveSelection::Down), empty_api_client_provider()));
+    swap_state(&state, event_bus::to_event(Message::Select(MoveSelection::Down), empty_api_client_provider()));
+
+    /* Refresh the topic config */
+    swap_state(&state, get_metadata());
+    let updated_state = state.borrow().clone();
+    let topic_info_state = updated_state.topic_info_state.expect("Should have selected topic info for topic");
+    assert_eq!(updated_state.selected_index, 1);
+    assert_eq!(topic_info_state.topic_metadata.topic, "bar");
+    assert_eq!(topic_info_state.selected_index, 2);
+    assert!(updated_state.last_refreshed_ms.is_some());
+
+    /* Refresh the topics while searching */
+    swap_state(&state, event_bus::to_event(Message::ToggleView(CurrentView::Topics), empty_api_client_provider()));
+    swap_state(&state, event_bus::to_event(Message::SetTopicQuery(TopicQuery::Query(String::from("ba"))), empty_api_client_provider()));
+    swap_state(&state, get_metadata());
+    let updated_state = state.borrow().clone();
+    assert_eq!(updated_state.current_view, CurrentView::Topics);
+    assert_eq!(updated_state.selected_topic_name(), Some(String::from("bar")));
+    assert_eq!(updated_state.topic_name_query, Some(String::from("ba")));
+}
+
+#[test]
+fn topics_consumer_lag() {
+    let state = RefCell::new(State::new());
+    let consumer_group = ConsumerGroup(String::from("group"), Coordinator { node_id: 1000, host: String::from("localhost"), port: 9092 });
+
+    let mut responses = HashMap::new();
+    // metadata, with partition leaders 1000 and 1001
+    responses.insert(3, three_topics_metadata());
+    // partition offsets of all topics
+    responses.insert(
+        2,
+        vec![
+            0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x1B, 0x5F, 0x5F, 0x63, 0x6F, 0x6E, 0x66, 0x6C, 0x75, 0x65,
+            0x6E, 0x74, 0x2E, 0x73, 0x75, 0x70, 0x70, 0x6F, 0x72, 0x74, 0x2E, 0x6D, 0x65, 0x74, 0x72, 0x69, 0x63, 0x73, 0x00, 0x00, 0x00, 0x01, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0x03,
+            0x66, 0x6F, 0x6F, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x64, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x64, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x64, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x64, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x64, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x64, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x64,
+            0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x64, 0x00,
+            0x00, 0x00, 0x08, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x64, 0x00, 0x00,
+            0x00, 0x09, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x64, 0x00, 0x00, 0x00,
+            0x0A, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x64, 0x00, 0x00, 0x00, 0x0B,
+            0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x64, 0x00, 0x00, 0x00, 0x0C, 0x00,
+            0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x64, 0x00, 0x00, 0x00, 0x0D, 0x00, 0x00,
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x64, 0x00, 0x00, 0x00, 0x0E, 0x00, 0x00, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x64, 0x00, 0x00, 0x00, 0x0F, 0x00, 0x00, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x64, 0x00, 0x03, 0x62, 0x61, 0x72, 0x00, 0x00, 0x00, 0x10,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00,
+            0x00, 0x00, 0x01, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00,
+            0x00, 0x02, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00,
+            0x03, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x04,
+            0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x05, 0x00,
+            0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00,
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x0B, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x0E, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x0F, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A,
+        ],
+    );
+    // committed offsets of 'group', none for 'bar'
+    responses.insert(
+        9,
+        vec![
+            0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x03, 0x66, 0x6F, 0x6F, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x5A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5A, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x5A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5A, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5A,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0B, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x5A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5A, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x0D, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0E, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x5A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5A, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x03, 0x62, 0x61, 0x72, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x05, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x08, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x09, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0A, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x0B, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0D, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0E, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x0F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ],
+    );
+    // min.insync.replicas of all topics
+    responses.insert(32, three_topics_min_isr());
+
+    /* Get metadata with the consumer group */
+    let requests = Arc::new(Mutex::new(vec![]));
+    let metadata_retrieved_event = event_bus::to_event(
+        Message::GetMetadata(test_bootstrap_server(), Some(consumer_group.clone())),
+        recording_api_client_provider(responses.clone(), requests.clone()),
+    );
+    swap_state(&state, metadata_retrieved_event);
+    let updated_state = state.borrow().clone();
+    assert_eq!(updated_state.topic_consumer_lag.get("foo"), Some(&160));
+    assert_eq!(updated_state.topic_consumer_lag.get("bar"), None);
+
+    /* One ListOffsets request per partition leader for the log end and log start offsets of all topics, and one OffsetFetch request */
+    assert_eq!(request_servers(&requests, 2), vec!["localhost:9092", "localhost:9092", "localhost:9093", "localhost:9093"]);
+    assert_eq!(request_servers(&requests, 9), vec!["localhost:9092"]); // the coordinator
+    assert_eq!(request_servers(&requests, 3), vec!["fake:9092"]);
+
+    /* The topics are still shown when the committed offsets can't be fetched */
+    // NOT_COORDINATOR
+    responses.insert(9, vec![0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10]);
+    let metadata_retrieved_event =
+        event_bus::to_event(Message::GetMetadata(test_bootstrap_server(), Some(consumer_group)), test_api_client_provider(responses.clone()));
+    swap_state(&state, metadata_retrieved_event);
+    let updated_state = state.borrow().clone();
+    assert_eq!(updated_state.metadata.map(|metadata| metadata.topic_metadata.len()), Some(3));
+    assert!(updated_state.topic_consumer_lag.is_empty());
+    assert_eq!(updated_state.topic_retained_messages.get("foo"), Some(&0));
+    match updated_state.dialog_message {
+        Some(DialogMessage::Warn(warning)) => assert!(warning.starts_with("Could not retrieve committed offsets")),
+        _ => panic!("Expected DialogMessage::Warn"),
+    }
+
+    /* Timed refreshes without a consumer group only retrieve the metadata, keeping the recently retrieved retained messages */
+    let requests = Arc::new(Mutex::new(vec![]));
+    let refresh = Message::RefreshMetadata(test_bootstrap_server(), None, Arc::new(AtomicBool::new(true)));
+    swap_state(&state, event_bus::to_event(refresh, recording_api_client_provider(responses.clone(), requests.clone())));
+    let updated_state = state.borrow().clone();
+    assert_eq!(request_servers(&requests, 3), vec!["fake:9092"]);
+    assert!(request_servers(&requests, 2).is_empty());
+    assert!(request_servers(&requests, 9).is_empty());
+    assert!(request_servers(&requests, 32).is_empty());
+    assert_eq!(updated_state.topic_retained_messages.get("foo"), Some(&0));
+}
+
+#[test]
+fn timed_refresh_keeps_view_and_dialog() {
+    let state = RefCell::new(State::new());
+
+    let mut responses = HashMap::new();
+    // metadata
+    responses.insert(3, two_brokers_metadata());
+
+    /* Show help while a message is displayed */
+    swap_state(&state, event_bus::to_event(Message::ToggleView(CurrentView::HelpScreen), empty_api_client_provider()));
+    swap_state(
+        &state,
+        event_bus::to_event(Message::DisplayUIMessage(DialogMessage::Info(String::from("Topic created"))), empty_api_client_provider()),
+    );
+
+    /* Timed refresh */
+    let refreshing = Arc::new(AtomicBool::new(true));
+    let refresh = Message::RefreshMetadata(test_bootstrap_server(), None, refreshing.clone());
+    swap_state(&state, event_bus::to_event(refresh, test_api_client_provider(responses.clone())));
+    let updated_state = state.borrow().clone();
+    assert!(updated_state.metadata.is_some());
+    assert_eq!(updated_state.current_view, CurrentView::HelpScreen);
+    match updated_state.dialog_message {
+        Some(DialogMessage::Info(info)) => assert_eq!(info, "Topic created"),
+        _ => panic!("Expected DialogMessage::Info"),
+    }
+    assert!(!refreshing.load(Ordering::SeqCst));
+
+    /* Refreshing with 'r' shows the topics */
+    swap_state(&state, event_bus::to_event(Message::GetMetadata(test_bootstrap_server(), None), test_api_client_provider(responses.clone())));
+    let updated_state = state.borrow().clone();
+    assert_eq!(updated_state.current_view, CurrentView::Topics);
+    assert!(updated_state.dialog_message.is_none());
+}
+
+#[test]
+fn metadata_failover() {
+    let state = RefCell::new(State::new());
+    let live_server = |state: &RefCell<State>| state.borrow().live_server.as_ref().map(|server| format!("{}:{}", server.host, server.port));
+
+    let mut responses = HashMap::new();
+    // metadata
+    responses.insert(3, two_brokers_metadata());
+
+    /* Get metadata from the bootstrap server */
+    let view_toggled = event_bus::to_event(Message::ToggleView(CurrentView::Brokers), empty_api_client_provider());
+    swap_state(&state, view_toggled);
+    let metadata_retrieved_event =
+        event_bus::to_event(Message::GetMetadata(test_bootstrap_server(), None), test_api_client_provider(responses.clone()));
+    swap_state(&state, metadata_retrieved_event);
+    assert_eq!(live_server(&state), Some(String::from("fake:9092")));
+
+    /* The bootstrap server is down, so the first known broker is used */
+    let metadata_retrieved_event =
+        event_bus::to_event(Message::GetMetadata(test_bootstrap_server(), None), unreachable_host_api_client_provider(responses.clone(), "fake"));
+    swap_state(&state, metadata_retrieved_event);
+    assert_eq!(live_server(&state), Some(String::from("localhost:9092")));
+    assert_eq!(state.borrow().metadata.as_ref().map(|metadata| metadata.brokers.len()), Some(2));
+
+    /* It keeps being used while it answers */
+    let requests = Arc::new(Mutex::new(vec![]));
+    let metadata_retrieved_event =
+        event_bus::to_event(Message::GetMetadata(test_bootstrap_server(), None), recording_api_client_provider(responses.clone(), requests.clone()));
+    swap_state(&state, metadata_retrieved_event);
+    assert_eq!(request_servers(&requests, 3), vec!["localhost:9092"]);
+    assert_eq!(live_server(&state), Some(String::from("localhost:9092")));
+}
+
+#[test]
+fn switch_cluster() {
+    let state = RefCell::new(State::new());
+
+    let event = event_bus::to_event(Message::SetTopicQuery(TopicQuery::Query(String::from("foo"))), empty_api_client_provider());
+    swap_state(&state, event);
+    let event = event_bus::to_event(Message::SetAutoRefresh(Some(5)), empty_api_client_provider());
+    swap_state(&state, event);
+    state.borrow_mut().live_server = Some(test_bootstrap_server());
+
+    let prod = Cluster { name: String::from("prod"), color: Some(String::from("red")) };
+    let event = event_bus::to_event(Message::SwitchCluster(prod.clone()), empty_api_client_provider());
+    swap_state(&state, event);
+
+    let updated_state = state.borrow().clone();
+    assert!(updated_state.metadata.is_none());
+    assert!(updated_state.live_server.is_none());
+    assert_eq!(updated_state.topic_name_query, None);
+    assert_eq!(updated_state.cluster, Some(prod));
+    assert_eq!(updated_state.auto_refresh_interval_secs, Some(5));
+}
@@ -1,10 +1,14 @@
 use std::cell::RefCell;
 use std::cell::RefMut;
+use std::cmp;
 use std::collections::HashMap;
 use std::io::{stdout, Write};
+use std::iter;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
 use std::sync::mpsc::Receiver;
 use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
 use std::thread;
 
 use termion::raw::IntoRawMode;
@@ -28,7 +32,8 @@ use crate::kafka_protocol::protocol_responses::*;
 use crate::state::CurrentView;
 use crate::state::*;
 use crate::user_interface::ui;
-use crate::util::utils::{controller_broker, Flatten};
+use crate::util::health;
+use crate::util::utils::{controller_broker, current_ms, iso_8601, Flatten};
 use crate::KafkaServerAddr;
 use crate::IO;
 
@@ -36,6 +41,7 @@ use crate::IO;
 pub struct ConsumerGroup(pub String, pub findcoordinator_response::Coordinator);
 
 const PAGE_MOVEMENT: i32 = 10;
+const TOPIC_DETAILS_INTERVAL_MS: u64 = 60_000; // how often timed refreshes retrieve the retained message counts and min ISR of all topics
 
 pub enum MoveSelection {
     Up,
@@ -60,32 +66,52 @@ pub enum Creation {
 pub enum Deletion {
     Topic(String),
     Config(String),
+    BrokerConfig(String),
 }
 
 pub enum Modification {
     Config(String),
+    BrokerConfig(String),
 }
 
 pub enum MetadataPayload {
     Metadata(metadata_response::MetadataResponse),
+    TopicsMetadata(
+        metadata_response::MetadataResponse,
+        HashMap<String, i64>,
+        HashMap<String, i64>,
+        Option<HashMap<String, i32>>,
+        Option<u64>,
+        HashMap<String, OffsetSnapshot>,
+        Vec<String>,
+    ),
     PartitionsMetadata(
         metadata_response::MetadataResponse,
         Vec<metadata_response::PartitionMetadata>,
         HashMap<i32, listoffsets_response::PartitionResponse>,
+        HashMap<i32, listoffsets_response::PartitionResponse>,
         Option<HashMap<i32, offsetfetch_response::PartitionResponse>>,
+        HashMap<String, OffsetSnapshot>,
     ),
     TopicInfoMetadata(metadata_response::MetadataResponse, describeconfigs_response::Resource),
+    BrokersMetadata(metadata_response::MetadataResponse),
+    BrokerInfoMetadata(metadata_response::MetadataResponse, describeconfigs_response::Resource),
+    HealthMetadata(metadata_response::MetadataResponse, Option<HashMap<String, i32>>),
 }
 
 pub enum Message {
     Quit,
     Noop,
     GetMetadata(KafkaServerAddr, Option<ConsumerGroup>),
+    RefreshMetadata(KafkaServerAddr, Option<ConsumerGroup>, Arc<AtomicBool>), // timed; the flag is cleared once the refresh is done
     ToggleView(CurrentView),
     DisplayUIMessage(DialogMessage),
     UserInput(String),
     Select(MoveSelection),
     SetTopicQuery(TopicQuery),
+    SetAutoRefresh(Option<u64>),
+    SwitchCluster(Cluster),
+    LookupOffsets(KafkaServerAddr, i64),
     Create(KafkaServerAddr, Creation, i32),
     Delete(KafkaServerAddr, i32),
     ModifyValue(KafkaServerAddr, Option<String>),
@@ -94,18 +120,23 @@ pub enum Message {
 enum Event {
     Exiting,
     StateIdentity,
-    MetadataRetrieved(StateFn<MetadataPayload>),
+    MetadataRetrieved(StateFn<(KafkaServerAddr, MetadataPayload)>),
+    MetadataRefreshed(StateFn<(KafkaServerAddr, MetadataPayload)>, Arc<AtomicBool>),
     ViewToggled(CurrentView),
     ShowUIMessage(DialogMessage),
     UserInputUpdated(String),
     SelectionUpdated(StateFn<(CurrentView, usize)>),
     TopicQuerySet(Option<String>),
+    AutoRefreshSet(Option<u64>),
+    ClusterSwitched(Cluster),
+    OffsetsLookedUp(StateFn<(String, TimestampOffsets)>),
     ResourceCreated(StateFn<String>),
     ResourceDeleted(StateFn<Deletion>),
     ValueModified(StateFn<Modification>),
 }
 
-pub fn start() -> Sender<Message> {
+/// `live_server` is kept up to date with the broker that metadata was last retrieved from
+pub fn start(live_server: Arc<Mutex<Option<KafkaServerAddr>>>) -> Sender<Message> {
     let (sender, receiver): (Sender<Message>, Receiver<Message>) = mpsc::channel();
     let thread_sender = sender.clone();
 
@@ -126,6 +157,7 @@ pub fn start() -> Sender<Message> {
                             thread_sender.send(Message::DisplayUIMessage(DialogMessage::Error(format!("{}: {}", error, cause)))).unwrap();
                         }
                     }
+                    *live_server.lock().unwrap() = state.borrow().live_server.clone();
                     ui::update_with_state(&state.borrow(), screen);
                 }
             }
@@ -145,57 +177,12 @@ fn to_event<T: ApiClientTrait + 'static>(message: Message, api_client_provider:
         UserInput(input) => UserInputUpdated(input),
         ToggleView(view) => ViewToggled(view),
 
-        GetMetadata(bootstrap_server, opt_consumer_group) => MetadataRetrieved(Box::from(move |state: &State| {
-            let metadata_response = retrieve_metadata(api_client_provider(), &bootstrap_server)
-                .into_result()
-                .map_err(|err| StateFNError::caused("Error encountered trying to retrieve topics", err));
-
-            match state.current_view {
-                CurrentView::Topics | CurrentView::HelpScreen => {
-                    metadata_response.map(|metadata_response| MetadataPayload::Metadata(metadata_response))
-                }
-                CurrentView::Partitions => metadata_response.and_then(|metadata_response| {
-                    state
-                        .selected_topic_metadata()
-                        .map(|topic_metadata| {
-                            retrieve_partition_metadata_and_offsets(api_client_provider(), &bootstrap_server, &metadata_response, &topic_metadata)
-                                .into_result()
-                                .and_then(|(partition_metadata, partition_offsets)| match opt_consumer_group {
-                                    None => Ok(MetadataPayload::PartitionsMetadata(metadata_response, partition_metadata, partition_offsets, None)),
-                                    Some(ConsumerGroup(ref group_id, ref coordinator)) => retrieve_consumer_offsets(
-                                        api_client_provider(),
-                                        group_id,
-                                        coordinator,
-                                        &topic_metadata,
-                                        bootstrap_server.use_tls,
-                                    )
-                                    .into_result()
-                                    .map(|consumer_offsets| {
-                                        MetadataPayload::PartitionsMetadata(
-                                            metadata_response,
-                                            partition_metadata,
-                                            partition_offsets,
-                                            Some(consumer_offsets),
-                                        )
-                                    }),
-                                })
-                                .map_err(|err| StateFNError::caused("Error retrieving partition metadata", err))
-                        })
-                        .unwrap_or(Err(StateFNError::error("Could not find selected topic metadata")))
-                }),
-                CurrentView::TopicInfo => metadata_response.and_then(|metadata_response| {
-                    state
-                        .selected_topic_name()
-                        .map(|topic_name| {
-                            retrieve_topic_metadata(api_client_provider(), &bootstrap_server, &topic_name)
-                                .into_result()
-                                .map_err(|err| StateFNError::caused("Error retrieving topic config", err))
-                                .map(|resource| MetadataPayload::TopicInfoMetadata(metadata_response, resource))
-                        })
-                        .unwrap_or(Err(StateFNError::error("No topic selected")))
-                }),
-            }
-        })),
+        GetMetadata(bootstrap_server, opt_consumer_group) => {
+            MetadataRetrieved(retrieve_metadata_payload(api_client_provider, bootstrap_server, opt_consumer_group, false))
+        }
+        RefreshMetadata(bootstrap_server, opt_consumer_group, refreshing) => {
+            MetadataRefreshed(retrieve_metadata_payload(api_client_provider, bootstrap_server, opt_consumer_group, true), refreshing)
+        }
 
         Select(direction) => {
             SelectionUpdated(Box::from(move |state: &State| {
@@ -307,6 +294,26 @@ fn to_event<T: ApiClientTrait + 'static>(message: Message, api_client_provider:
                             .unwrap_or(0);
                         Ok((CurrentView::TopicInfo, selected_index))
                     }
+                    CurrentView::Brokers => {
+                        // the brokers are followed by the cluster-wide defaults
+                        let brokers_len = state.metadata.as_ref().map(|metadata| metadata.brokers.len() + 1).unwrap_or(0);
+                        Ok((CurrentView::Brokers, move_selection(&direction, state.selected_broker_index, brokers_len)))
+                    }
+                    CurrentView::BrokerInfo => {
+                        let selected_index = state
+                            .broker_info_state
+                            .as_ref()
+                            .map(|broker_info_state| {
+                                move_selection(&direction, broker_info_state.selected_index, broker_info_state.config_resource.config_entries.len())
+                            })
+                            .unwrap_or(0);
+                        Ok((CurrentView::BrokerInfo, selected_index))
+                    }
+                    CurrentView::Health => {
+                        let unhealthy_len =
+                            state.metadata.as_ref().map(|metadata| health::unhealthy_partitions(metadata, &state.topic_min_isr).len()).unwrap_or(0);
+                        Ok((CurrentView::Health, move_selection(&direction, state.selected_health_index, unhealthy_len)))
+                    }
                 }
             }))
         }
@@ -316,6 +323,27 @@ fn to_event<T: ApiClientTrait + 'static>(message: Message, api_client_provider:
             NoQuery => TopicQuerySet(None),
         },
 
+        SetAutoRefresh(interval_secs) => AutoRefreshSet(interval_secs),
+
+        SwitchCluster(cluster) => ClusterSwitched(cluster),
+
+        LookupOffsets(bootstrap_server, timestamp) => OffsetsLookedUp(Box::from(move |state: &State| match state.current_view {
+            CurrentView::Partitions => match (state.metadata.as_ref(), state.selected_topic_metadata()) {
+                (Some(metadata_response), Some(topic_metadata)) => retrieve_partition_offsets(
+                    api_client_provider(),
+                    &live_server(state, &bootstrap_server),
+                    metadata_response,
+                    &topic_metadata,
+                    timestamp,
+                )
+                .into_result()
+                .map(|offsets| (topic_metadata.topic.clone(), TimestampOffsets { timestamp, offsets }))
+                .map_err(|err| StateFNError::caused("Error looking up offsets", err)),
+                _ => Err(StateFNError::error("Could not find selected topic metadata")),
+            },
+            _ => Err(StateFNError::error("Offsets can only be looked up in the partitions view")),
+        })),
+
         Create(bootstrap_sever, creation, request_timeout_ms) => ResourceCreated(Box::from(move |state: &State| match &creation {
             Creation::Topic { name, partitions, replication_factor } => {
                 if state.current_view != CurrentView::Topics {
@@ -369,6 +397,19 @@ fn to_event<T: ApiClientTrait + 'static>(message: Message, api_client_provider:
         Delete(bootstrap_server, request_timeout_ms) => ResourceDeleted(Box::from(move |state: &State| match state.current_view {
             CurrentView::Partitions => Err(StateFNError::error("Partition deletion not supported")),
             CurrentView::HelpScreen => Err(StateFNError::error("You can't delete this...")),
+            CurrentView::Health => Err(StateFNError::error("Partition deletion not supported")),
+            CurrentView::Brokers => Err(StateFNError::error("Broker deletion not supported")),
+            CurrentView::BrokerInfo => state
+                .broker_info_state
+                .as_ref()
+                .map(|broker_info_state| match broker_info_state.config_resource.config_entries.get(broker_info_state.selected_index) {
+                    None => Err(StateFNError::error("Error trying to modify selected config")),
+                    Some(config_entry) => existing_broker_configs(broker_info_state, config_entry).and_then(|existing_configs| {
+                        let (server, resource) = broker_config_resource(broker_info_state, &live_server(state, &bootstrap_server), existing_configs);
+                        alter_config(api_client_provider(), &server, &resource).map(|_| Deletion::BrokerConfig(config_entry.config_name.clone()))
+                    }),
+                })
+                .unwrap_or(Err(StateFNError::error("Broker info not available"))),
             CurrentView::Topics => state
                 .metadata
                 .as_ref()
@@ -438,7 +479,8 @@ fn to_event<T: ApiClientTrait + 'static>(message: Message, api_client_provider:
                             config_entries: existing_configs,
                         };
 
-                        alter_config(api_client_provider(), &bootstrap_server, &resource).map(|_| Deletion::Config(config_entry.config_name.clone()))
+                        alter_config(api_client_provider(), &live_server(state, &bootstrap_server), &resource)
+                            .map(|_| Deletion::Config(config_entry.config_name.clone()))
                     }
                 })
                 .unwrap_or(Err(StateFNError::error("Topic metadata not available"))),
@@ -448,6 +490,23 @@ fn to_event<T: ApiClientTrait + 'static>(message: Message, api_client_provider:
             CurrentView::Topics => Err(StateFNError::error("Modifications not supported for topics")),
             CurrentView::Partitions => Err(StateFNError::error("Modifications not supported for partitions")),
             CurrentView::HelpScreen => Err(StateFNError::error("You can't modify this...")),
+            CurrentView::Health => Err(StateFNError::error("Modifications not supported for partitions")),
+            CurrentView::Brokers => Err(StateFNError::error("Modifications not supported for brokers")),
+            CurrentView::BrokerInfo => state
+                .broker_info_state
+                .as_ref()
+                .map(|broker_info_state| match broker_info_state.config_resource.config_entries.get(broker_info_state.selected_index) {
+                    None => Err(StateFNError::error("Error trying to modify selected config")),
+                    Some(config_entry) => existing_broker_configs(broker_info_state, config_entry).and_then(|mut existing_configs| {
+                        existing_configs.push(alterconfigs_request::ConfigEntry {
+                            config_name: config_entry.config_name.clone(),
+                            config_value: new_value.clone(),
+                        });
+                        let (server, resource) = broker_config_resource(broker_info_state, &live_server(state, &bootstrap_server), existing_configs);
+                        alter_config(api_client_provider(), &server, &resource).map(|_| Modification::BrokerConfig(config_entry.config_name.clone()))
+                    }),
+                })
+                .unwrap_or(Err(StateFNError::error("Broker info not available"))),
             CurrentView::TopicInfo => state
                 .topic_info_state
                 .as_ref()
@@ -474,7 +533,7 @@ fn to_event<T: ApiClientTrait + 'static>(message: Message, api_client_provider:
                             config_entries: existing_configs,
                         };
 
-                        alter_config(api_client_provider(), &bootstrap_server, &resource)
+                        alter_config(api_client_provider(), &live_server(state, &bootstrap_server), &resource)
                             .map(|_| Modification::Config(config_entry.config_name.clone()))
                     }
                 })
@@ -498,27 +557,12 @@ fn update_state(event: Event, mut current_state: RefMut<State>) -> Result<State,
             }
             Ok(current_state.clone())
         }
-        MetadataRetrieved(payload_fn) => payload_fn(&current_state).and_then(|payload: MetadataPayload| match payload {
-            MetadataPayload::Metadata(metadata_response) => {
-                let mut state = State::new();
-                state.current_view = CurrentView::Topics;
-                state.selected_index = current_state.selected_index;
-                state.metadata = Some(metadata_response);
-                Ok(state)
-            }
-            MetadataPayload::PartitionsMetadata(metadata_response, partition_metadata, partition_offsets, consumer_offsets) => {
-                current_state.metadata = Some(metadata_response);
-                current_state.partition_info_state =
-                    Some(PartitionInfoState::new(partition_metadata, partition_offsets, consumer_offsets.unwrap_or(HashMap::new())));
-                Ok(current_state.clone())
-            }
-            MetadataPayload::TopicInfoMetadata(metadata_response, config_resources) => {
-                current_state.metadata = Some(metadata_response);
-                current_state.topic_info_state =
-                    current_state.selected_topic_metadata().map(|topic_metadata| TopicInfoState::new(topic_metadata, config_resources));
-                Ok(current_state.clone())
-            }
-        }),
+        MetadataRetrieved(payload_fn) => metadata_retrieved(payload_fn, current_state, false),
+        MetadataRefreshed(payload_fn, refreshing) => {
+            let updated_state = metadata_retrieved(payload_fn, current_state, true);
+            refreshing.store(false, Ordering::SeqCst); // lets the next timed refresh be sent
+            updated_state
+        }
         ViewToggled(view) => {
             current_state.current_view = view;
             Ok(current_state.clone())
@@ -544,11 +588,49 @@ fn update_state(event: Event, mut current_state: RefMut<State>) -> Result<State,
                 });
                 Ok(current_state.clone())
             }
+            Ok((CurrentView::Brokers, selected_index)) => {
+                current_state.selected_broker_index = selected_index;
+                Ok(current_state.clone())
+            }
+            Ok((CurrentView::Health, selected_index)) => {
+                current_state.selected_health_index = selected_index;
+                Ok(current_state.clone())
+            }
+            Ok((CurrentView::BrokerInfo, selected_index)) => {
+                current_state.broker_info_state = current_state.broker_info_state.as_mut().map(|state| {
+                    state.selected_index = selected_index;
+                    state.clone()
+                });
+                Ok(current_state.clone())
+            }
         },
         TopicQuerySet(query) => {
             current_state.topic_name_query = query;
             Ok(current_state.clone())
         }
+        AutoRefreshSet(interval_secs) => {
+            current_state.auto_refresh_interval_secs = interval_secs;
+            Ok(current_state.clone())
+        }
+        ClusterSwitched(cluster) => {
+            // nothing of the previous cluster carries over, only the UI settings. This includes the live server, so the
+            // next requests go to the new bootstrap server
+            let mut state = State::new();
+            state.cluster = Some(cluster);
+            state.auto_refresh_interval_secs = current_state.auto_refresh_interval_secs;
+            Ok(state)
+        }
+        OffsetsLookedUp(lookup_fn) => lookup_fn(&current_state).map(|(topic, timestamp_offsets)| {
+            current_state.dialog_message = Some(DialogMessage::Info(format!("AT: offsets at {}", iso_8601(timestamp_offsets.timestamp as u64))));
+            let current_partition_info_state = current_state.partition_info_state.clone();
+            current_state.partition_info_state = current_partition_info_state.map(|mut partition_info_state| {
+                if partition_info_state.topic == topic {
+                    partition_info_state.timestamp_offsets = Some(timestamp_offsets);
+                }
+                partition_info_state
+            });
+            current_state.clone()
+        }),
         ResourceCreated(create_fn) => create_fn(&current_state).map(|new_topic_name| {
             current_state.dialog_message = Some(DialogMessage::Info(format!("Topic '{}' created. Press 'r' to refresh view.", new_topic_name)));
             current_state.clone()
@@ -566,20 +648,372 @@ fn update_state(event: Event, mut current_state: RefMut<State>) -> Result<State,
                 });
                 current_state.clone()
             }
+            Deletion::BrokerConfig(config) => {
+                let current_broker_info_state = current_state.broker_info_state.clone();
+                current_state.broker_info_state = current_broker_info_state.map(|mut broker_info_state| {
+                    broker_info_state.configs_marked_deleted.push(config);
+                    broker_info_state
+                });
+                current_state.clone()
+            }
         }),
-        ValueModified(modify_fn) => modify_fn(&current_state).map(|modification: Modification| {
-            let current_topic_info_state = current_state.topic_info_state.clone();
-            current_state.topic_info_state = current_topic_info_state.map(|mut topic_info_state| {
-                match modification {
-                    Modification::Config(config_name) => topic_info_state.configs_marked_modified.push(config_name),
-                }
-                topic_info_state
-            });
-            current_state.clone()
+        ValueModified(modify_fn) => modify_fn(&current_state).map(|modification: Modification| match modification {
+            Modification::Config(config_name) => {
+                let current_topic_info_state = current_state.topic_info_state.clone();
+                current_state.topic_info_state = current_topic_info_state.map(|mut topic_info_state| {
+                    topic_info_state.configs_marked_modified.push(config_name);
+                    topic_info_state
+                });
+                current_state.clone()
+            }
+            Modification::BrokerConfig(config_name) => {
+                let current_broker_info_state = current_state.broker_info_state.clone();
+                current_state.broker_info_state = current_broker_info_state.map(|mut broker_info_state| {
+                    broker_info_state.configs_marked_modified.push(config_name);
+                    broker_info_state
+                });
+                current_state.clone()
+            }
         }),
     }
 }
 
+/// The metadata of the current view, retrieved from the first broker that answers
+fn retrieve_metadata_payload<T: ApiClientTrait + 'static>(
+    api_client_provider: ApiClientProvider<T>,
+    bootstrap_server: KafkaServerAddr,
+    opt_consumer_group: Option<ConsumerGroup>,
+    timed_refresh: bool,
+) -> StateFn<(KafkaServerAddr, MetadataPayload)> {
+    Box::from(move |state: &State| {
+        let live_metadata_response = retrieve_metadata_with_failover(&api_client_provider, &bootstrap_server, state)
+            .map_err(|err| StateFNError::caused("Error encountered trying to retrieve topics", err));
+        // the rest of the refresh is sent to the broker that answered
+        let bootstrap_server = live_metadata_response.as_ref().map(|(server, _)| server.clone()).unwrap_or(bootstrap_server.clone());
+        let metadata_response = live_metadata_response.map(|(_, metadata_response)| metadata_response);
+
+        let payload = match state.current_view {
+            CurrentView::HelpScreen => metadata_response.map(|metadata_response| MetadataPayload::Metadata(metadata_response)),
+            CurrentView::Topics => metadata_response.map(|metadata_response| {
+                // the offsets and min.insync.replicas only add lag, retained message counts, rates and health to the topics,
+                // so the topics are still shown without them
+                let mut warnings = vec![];
+                // retained message counts and min.insync.replicas change slowly, so timed refreshes only retrieve them now and then
+                let details_due = !timed_refresh
+                    || state
+                        .topic_details_retrieved_ms
+                        .map(|retrieved_ms| current_ms().saturating_sub(retrieved_ms) >= TOPIC_DETAILS_INTERVAL_MS)
+                        .unwrap_or(true);
+
+                let log_end_offsets = if opt_consumer_group.is_some() || details_due {
+                    let log_end_offsets = retrieve_topic_offsets(api_client_provider(), &bootstrap_server, &metadata_response, -1).into_result();
+                    retrieved_or_warn(log_end_offsets, "log end offsets", &mut warnings)
+                } else {
+                    None
+                };
+
+                let consumer_offsets = match (&log_end_offsets, &opt_consumer_group) {
+                    (Some(_), Some(ConsumerGroup(ref group_id, ref coordinator))) => {
+                        let consumer_offsets = retrieve_consumer_offsets(
+                            api_client_provider(),
+                            group_id,
+                            coordinator,
+                            &metadata_response.topic_metadata,
+                            bootstrap_server.use_tls,
+                        )
+                        .into_result();
+                        retrieved_or_warn(consumer_offsets.map(|consumer_offsets| (consumer_offsets, vec![])), "committed offsets", &mut warnings)
+                    }
+                    _ => None,
+                };
+
+                let topic_consumer_lag = match (&log_end_offsets, &consumer_offsets) {
+                    (Some(log_end_offsets), Some(consumer_offsets)) => consumer_lag(log_end_offsets, consumer_offsets),
+                    _ => HashMap::new(),
+                };
+
+                let group_id = opt_consumer_group.as_ref().map(|ConsumerGroup(group_id, _)| group_id);
+                let offset_snapshots = log_end_offsets
+                    .as_ref()
+                    .map(|log_end_offsets| {
+                        log_end_offsets
+                            .iter()
+                            .map(|(topic, partition_offsets)| {
+                                let topic_consumer_offsets = consumer_offsets.as_ref().and_then(|consumer_offsets| consumer_offsets.get(topic));
+                                (topic.clone(), offset_snapshot(group_id, partition_offsets, topic_consumer_offsets))
+                            })
+                            .collect::<HashMap<String, OffsetSnapshot>>()
+                    })
+                    .unwrap_or(HashMap::new());
+
+                let (topic_retained_messages, topic_min_isr, topic_details_retrieved_ms) = if details_due {
+                    let topic_retained_messages = log_end_offsets
+                        .as_ref()
+                        .and_then(|log_end_offsets| {
+                            let log_start_offsets =
+                                retrieve_topic_offsets(api_client_provider(), &bootstrap_server, &metadata_response, -2).into_result();
+                            retrieved_or_warn(log_start_offsets, "log start offsets", &mut warnings)
+                                .map(|log_start_offsets| retained_messages(log_end_offsets, &log_start_offsets))
+                        })
+                        .unwrap_or(HashMap::new());
+                    let topic_min_isr = retrieve_topic_min_isr(api_client_provider(), &bootstrap_server, &metadata_response).into_result();
+                    let topic_min_isr =
+                        retrieved_or_warn(topic_min_isr.map(|topic_min_isr| (topic_min_isr, vec![])), "min.insync.replicas", &mut warnings);
+                    (topic_retained_messages, topic_min_isr, Some(current_ms()))
+                } else {
+                    (state.topic_retained_messages.clone(), state.topic_min_isr.clone(), state.topic_details_retrieved_ms)
+                };
+
+                MetadataPayload::TopicsMetadata(
+                    metadata_response,
+                    topic_consumer_lag,
+                    topic_retained_messages,
+                    topic_min_isr,
+                    topic_details_retrieved_ms,
+                    offset_snapshots,
+                    warnings,
+                )
+            }),
+            CurrentView::Partitions => metadata_response.and_then(|metadata_response| {
+                state
+                    .selected_topic_metadata()
+                    .map(|topic_metadata| {
+                        retrieve_partition_metadata_and_offsets(api_client_provider(), &bootstrap_server, &metadata_response, &topic_metadata)
+                            .into_result()
+                            .and_then(|(partition_metadata, partition_offsets, log_start_offsets)| match opt_consumer_group {
+                                None => {
+                                    let mut offset_snapshots = HashMap::new();
+                                    offset_snapshots.insert(topic_metadata.topic.clone(), offset_snapshot(None, &partition_offsets, None));
+                                    Ok(MetadataPayload::PartitionsMetadata(
+                                        metadata_response,
+                                        partition_metadata,
+                                        partition_offsets,
+                                        log_start_offsets,
+                                        None,
+                                        offset_snapshots,
+                                    ))
+                                }
+                                Some(ConsumerGroup(ref group_id, ref coordinator)) => retrieve_consumer_offsets(
+                                    api_client_provider(),
+                                    group_id,
+                                    coordinator,
+                                    &vec![topic_metadata.clone()],
+                                    bootstrap_server.use_tls,
+                                )
+                                .into_result()
+                                .and_then(|mut consumer_offsets| {
+                                    consumer_offsets.remove(&topic_metadata.topic).ok_or(ApiRequestError::from("Topic not returned from API request"))
+                                })
+                                .map(|consumer_offsets| {
+                                    let mut offset_snapshots = HashMap::new();
+                                    offset_snapshots.insert(
+                                        topic_metadata.topic.clone(),
+                                        offset_snapshot(Some(group_id), &partition_offsets, Some(&consumer_offsets)),
+                                    );
+                                    MetadataPayload::PartitionsMetadata(
+                                        metadata_response,
+                                        partition_metadata,
+                                        partition_offsets,
+                                        log_start_offsets,
+                                        Some(consumer_offsets),
+                                        offset_snapshots,
+                                    )
+                                }),
+                            })
+                            .map_err(|err| StateFNError::caused("Error retrieving partition metadata", err))
+                    })
+                    .unwrap_or(Err(StateFNError::error("Could not find selected topic metadata")))
+            }),
+            CurrentView::TopicInfo => metadata_response.and_then(|metadata_response| {
+                state
+                    .selected_topic_name()
+                    .map(|topic_name| {
+                        retrieve_config_resource(api_client_provider(), &bootstrap_server, protocol_requests::ResourceTypes::Topic as i8, &topic_name)
+                            .into_result()
+                            .map_err(|err| StateFNError::caused("Error retrieving topic config", err))
+                            .map(|resource| MetadataPayload::TopicInfoMetadata(metadata_response, resource))
+                    })
+                    .unwrap_or(Err(StateFNError::error("No topic selected")))
+            }),
+            CurrentView::Brokers => metadata_response.map(|metadata_response| MetadataPayload::BrokersMetadata(metadata_response)),
+            CurrentView::Health => metadata_response.map(|metadata_response| {
+                let topic_min_isr = retrieve_topic_min_isr(api_client_provider(), &bootstrap_server, &metadata_response).into_result().ok();
+                MetadataPayload::HealthMetadata(metadata_response, topic_min_isr)
+            }),
+            CurrentView::BrokerInfo if state.selected_cluster_default() => metadata_response.and_then(|metadata_response| {
+                controller_broker(&metadata_response)
+                    .map(|controller| {
+                        let controller_server = KafkaServerAddr::of(controller.host.clone(), controller.port, bootstrap_server.use_tls);
+                        retrieve_cluster_default_configs(api_client_provider(), &controller_server, controller.node_id)
+                            .into_result()
+                            .map_err(|err| StateFNError::caused("Error retrieving cluster-wide broker config", err))
+                    })
+                    .unwrap_or(Err(StateFNError::error("Could not find Kafka controller host from Metadata")))
+                    .map(|resource| MetadataPayload::BrokerInfoMetadata(metadata_response, resource))
+            }),
+            CurrentView::BrokerInfo => metadata_response.and_then(|metadata_response| {
+                state
+                    .selected_broker_metadata()
+                    .map(|broker_metadata| {
+                        // broker configs are only described by the broker itself
+                        let broker_server = KafkaServerAddr::of(broker_metadata.host.clone(), broker_metadata.port, bootstrap_server.use_tls);
+                        retrieve_config_resource(
+                            api_client_provider(),
+                            &broker_server,
+                            protocol_requests::ResourceTypes::Broker as i8,
+                            &broker_metadata.node_id.to_string(),
+                        )
+                        .into_result()
+                        .map_err(|err| StateFNError::caused("Error retrieving broker config", err))
+                        .map(|resource| MetadataPayload::BrokerInfoMetadata(metadata_response, resource))
+                    })
+                    .unwrap_or(Err(StateFNError::error("No broker selected")))
+            }),
+        };
+        payload.map(|payload| (bootstrap_server.clone(), payload))
+    })
+}
+
+/// Updates the state of the current view with the retrieved metadata
+fn metadata_retrieved(
+    payload_fn: StateFn<(KafkaServerAddr, MetadataPayload)>,
+    mut current_state: RefMut<State>,
+    timed_refresh: bool,
+) -> Result<State, StateFNError> {
+    payload_fn(&current_state).and_then(|(live_server, payload): (KafkaServerAddr, MetadataPayload)| {
+        let updated_state = match payload {
+            MetadataPayload::Metadata(metadata_response) => Ok(refreshed_topics_state(&current_state, metadata_response, timed_refresh)),
+            MetadataPayload::TopicsMetadata(
+                metadata_response,
+                topic_consumer_lag,
+                topic_retained_messages,
+                topic_min_isr,
+                topic_details_retrieved_ms,
+                offset_snapshots,
+                warnings,
+            ) => {
+                let mut state = refreshed_topics_state(&current_state, metadata_response, timed_refresh);
+                state.topic_consumer_lag = topic_consumer_lag;
+                state.topic_retained_messages = topic_retained_messages;
+                state.topic_min_isr = topic_min_isr;
+                state.topic_details_retrieved_ms = topic_details_retrieved_ms;
+                state.record_offset_snapshots(offset_snapshots);
+                if !warnings.is_empty() {
+                    state.dialog_message = Some(DialogMessage::Warn(warnings.join(". ")));
+                }
+                Ok(state)
+            }
+            MetadataPayload::PartitionsMetadata(
+                metadata_response,
+                partition_metadata,
+                partition_offsets,
+                log_start_offsets,
+                consumer_offsets,
+                offset_snapshots,
+            ) => {
+                current_state.set_metadata(metadata_response);
+                current_state.record_offset_snapshots(offset_snapshots);
+                current_state.partition_info_state = current_state.selected_topic_name().map(|topic_name| {
+                    PartitionInfoState::new(
+                        topic_name,
+                        partition_metadata,
+                        partition_offsets,
+                        log_start_offsets,
+                        consumer_offsets.unwrap_or(HashMap::new()),
+                    )
+                    .retain_selection(&current_state.partition_info_state)
+                });
+                Ok(current_state.clone())
+            }
+            MetadataPayload::TopicInfoMetadata(metadata_response, config_resources) => {
+                current_state.set_metadata(metadata_response);
+                current_state.topic_info_state = current_state
+                    .selected_topic_metadata()
+                    .map(|topic_metadata| TopicInfoState::new(topic_metadata, config_resources).retain_selection(&current_state.topic_info_state));
+                Ok(current_state.clone())
+            }
+            MetadataPayload::BrokersMetadata(metadata_response) => {
+                current_state.set_metadata(metadata_response);
+                Ok(current_state.clone())
+            }
+            MetadataPayload::BrokerInfoMetadata(metadata_response, config_resource) => {
+                current_state.set_metadata(metadata_response);
+                current_state.broker_info_state = Some(
+                    BrokerInfoState::new(current_state.selected_broker_metadata(), config_resource)
+                        .retain_selection(&current_state.broker_info_state),
+                );
+                Ok(current_state.clone())
+            }
+            MetadataPayload::HealthMetadata(metadata_response, topic_min_isr) => {
+                // the unhealthy partitions may have changed, so keep the selection within them
+                let unhealthy_len = health::unhealthy_partitions(&metadata_response, &topic_min_isr).len();
+                current_state.selected_health_index = cmp::min(current_state.selected_health_index, unhealthy_len.saturating_sub(1));
+                current_state.set_metadata(metadata_response);
+                current_state.topic_min_isr = topic_min_isr;
+                Ok(current_state.clone())
+            }
+        };
+        updated_state.map(|mut state| {
+            state.live_server = Some(live_server);
+            state.last_refreshed_ms = Some(current_ms());
+            state
+        })
+    })
+}
+
+/// The topics view is shown with the refreshed metadata, keeping what was selected, searched for and marked.
+/// A timed refresh also keeps the current view and dialog, so it doesn't close the help screen or a message being read
+fn refreshed_topics_state(current_state: &State, metadata_response: metadata_response::MetadataResponse, timed_refresh: bool) -> State {
+    let mut state = current_state.clone();
+    if !timed_refresh {
+        state.current_view = CurrentView::Topics;
+        state.dialog_message = None;
+    }
+    state.set_metadata(metadata_response);
+    state
+}
+
+/// The retrieved value, or None with a warning for the dialog when it, or part of it, could not be retrieved
+fn retrieved_or_warn<A>(result: Result<(A, Vec<ApiRequestError>), ApiRequestError>, what: &str, warnings: &mut Vec<String>) -> Option<A> {
+    match result {
+        Ok((retrieved, errors)) => {
+            if let Some(error) = errors.first() {
+                warnings.push(format!("Some {} could not be retrieved: {}", what, error));
+            }
+            Some(retrieved)
+        }
+        Err(error) => {
+            warnings.push(format!("Could not retrieve {}: {}", what, error));
+            None
+        }
+    }
+}
+
+/// The broker that metadata was last retrieved from, which can differ from the bootstrap server after a failover
+fn live_server(state: &State, bootstrap_server: &KafkaServerAddr) -> KafkaServerAddr {
+    state.live_server.clone().unwrap_or(bootstrap_server.clone())
+}
+
+/// Tries the broker that last answered (or the bootstrap server), then the other brokers of the last known metadata
+fn retrieve_metadata_with_failover<T: ApiClientTrait + 'static>(
+    api_client_provider: &ApiClientProvider<T>,
+    bootstrap_server: &KafkaServerAddr,
+    state: &State,
+) -> Result<(KafkaServerAddr, metadata_response::MetadataResponse), ApiRequestError> {
+    let known_brokers = state
+        .metadata
+        .iter()
+        .flat_map(|metadata| metadata.brokers.iter())
+        .map(|broker| KafkaServerAddr::of(broker.host.clone(), broker.port, bootstrap_server.use_tls));
+
+    iter::once(live_server(state, bootstrap_server)).chain(known_brokers).fold(
+        Err(ApiRequestError::from("No Kafka brokers to retrieve metadata from")),
+        |result, server| {
+            result.or_else(|_| retrieve_metadata(api_client_provider(), &server).into_result().map(|metadata_response| (server, metadata_response)))
+        },
+    )
+}
+
 fn retrieve_metadata<T: ApiClientTrait + 'static>(
     client: IO<T, ApiRequestError>,
     bootstrap_server: &KafkaServerAddr,
@@ -591,43 +1025,39 @@ fn retrieve_metadata<T: ApiClientTrait + 'static>(
 
         result.map(|response| {
             let mut metadata_response = response.response_message;
-            // sort by topic names before returning
+            // sort by topic names and broker ids before returning
             metadata_response.topic_metadata.sort_by(|a, b| a.topic.to_lowercase().cmp(&b.topic.to_lowercase()));
+            metadata_response.brokers.sort_by(|a, b| a.node_id.cmp(&b.node_id));
             metadata_response
         })
     }))
 }
 
-fn retrieve_topic_metadata<T: ApiClientTrait + 'static>(
+fn retrieve_config_resource<T: ApiClientTrait + 'static>(
     client: IO<T, ApiRequestError>,
-    bootstrap_server: &KafkaServerAddr,
-    topic_name: &String,
+    server: &KafkaServerAddr,
+    resource_type: i8,
+    resource_name: &String,
 ) -> IO<describeconfigs_response::Resource, ApiRequestError> {
-    let bootstrap_server = bootstrap_server.clone();
-    let topic_name = topic_name.clone();
+    let server = server.clone();
+    let resource_name = resource_name.clone();
 
     client.and_then_result(Box::new(move |client: T| {
-        let resource = describeconfigs_request::Resource {
-            resource_type: protocol_requests::ResourceTypes::Topic as i8,
-            resource_name: topic_name.clone(),
-            config_names: None,
-        };
+        let resource = describeconfigs_request::Resource { resource_type, resource_name: resource_name.clone(), config_names: None };
 
-        let result: Result<Response<describeconfigs_response::DescribeConfigsResponse>, ApiRequestError> = client.request(
-            &bootstrap_server,
-            Request::of(describeconfigs_request::DescribeConfigsRequest { resources: vec![resource], include_synonyms: false }),
-        );
+        let result: Result<Response<describeconfigs_response::DescribeConfigsResponse>, ApiRequestError> = client
+            .request(&server, Request::of(describeconfigs_request::DescribeConfigsRequest { resources: vec![resource], include_synonyms: false }));
 
         result.and_then(|response| {
             let resource = response
                 .response_message
                 .resources
                 .into_iter()
-                .filter(|resource| resource.resource_name.eq(&topic_name))
+                .filter(|resource| resource.resource_name.eq(&resource_name))
                 .collect::<Vec<describeconfigs_response::Resource>>();
 
             match resource.first() {
-                None => Err(ApiRequestError::from("API response missing topic resource info")),
+                None => Err(ApiRequestError::from("API response missing resource info")),
                 Some(resource) => {
                     if resource.error_code == 0 {
                         Ok(resource.clone())
@@ -641,96 +1071,352 @@ fn retrieve_topic_metadata<T: ApiClientTrait + 'static>(
     }))
 }
 
+/// Describes min.insync.replicas of every topic with a single request
+fn retrieve_topic_min_isr<T: ApiClientTrait + 'static>(
+    client: IO<T, ApiRequestError>,
+    bootstrap_server: &KafkaServerAddr,
+    metadata: &metadata_response::MetadataResponse,
+) -> IO<HashMap<String, i32>, ApiRequestError> {
+    let bootstrap_server = bootstrap_server.clone();
+    let topics = metadata.topic_metadata.iter().map(|topic_metadata| topic_metadata.topic.clone()).collect::<Vec<String>>();
+
+    client.and_then_result(Box::new(move |client: T| {
+        let resources = topics
+            .iter()
+            .map(|topic| describeconfigs_request::Resource {
+                resource_type: protocol_requests::ResourceTypes::Topic as i8,
+                resource_name: topic.clone(),
+                config_names: Some(vec![String::from(health::MIN_INSYNC_REPLICAS)]),
+            })
+            .collect::<Vec<describeconfigs_request::Resource>>();
+
+        let result: Result<Response<describeconfigs_response::DescribeConfigsResponse>, ApiRequestError> =
+            client.request(&bootstrap_server, Request::of(describeconfigs_request::DescribeConfigsRequest { resources, include_synonyms: false }));
+
+        result.map(|response| {
+            response
+                .response_message
+                .resources
+                .into_iter()
+                .filter(|resource| resource.error_code == 0)
+                .filter_map(|resource| {
+                    resource
+                        .config_entries
+                        .iter()
+                        .find(|config_entry| config_entry.config_name == health::MIN_INSYNC_REPLICAS)
+                        .and_then(|config_entry| config_entry.config_value.as_ref().and_then(|value| value.parse::<i32>().ok()))
+                        .map(|min_isr| (resource.resource_name.clone(), min_isr))
+                })
+                .collect::<HashMap<String, i32>>()
+        })
+    }))
+}
+
+/// Describes the cluster-wide broker defaults, listed against the controller's dynamically updatable configs
+fn retrieve_cluster_default_configs<T: ApiClientTrait + 'static>(
+    client: IO<T, ApiRequestError>,
+    controller_server: &KafkaServerAddr,
+    controller_id: i32,
+) -> IO<describeconfigs_response::Resource, ApiRequestError> {
+    let controller_server = controller_server.clone();
+
+    client.and_then_result(Box::new(move |client: T| {
+        let resources = vec![String::from(""), controller_id.to_string()]
+            .into_iter()
+            .map(|resource_name| describeconfigs_request::Resource {
+                resource_type: protocol_requests::ResourceTypes::Broker as i8,
+                resource_name,
+                config_names: None,
+            })
+            .collect::<Vec<describeconfigs_request::Resource>>();
+
+        let result: Result<Response<describeconfigs_response::DescribeConfigsResponse>, ApiRequestError> =
+            client.request(&controller_server, Request::of(describeconfigs_request::DescribeConfigsRequest { resources, include_synonyms: false }));
+
+        result.and_then(|response| {
+            let resources = response.response_message.resources;
+
+            match resources.iter().find(|resource| resource.error_code != 0) {
+                Some(resource) => {
+                    let error_msg = resource.error_message.clone().unwrap_or(format!(""));
+                    Err(ApiRequestError::of(format!("Error describing config. {}", error_msg)))
+                }
+                None => {
+                    let cluster_defaults = resources.iter().find(|resource| resource.resource_name.is_empty());
+                    let controller = resources.iter().find(|resource| !resource.resource_name.is_empty());
+
+                    match (cluster_defaults, controller) {
+                        (Some(cluster_defaults), Some(controller)) => {
+                            let mut resource = cluster_defaults.clone();
+                            resource.config_entries = controller
+                                .config_entries
+                                .iter()
+                                .filter(|config_entry| !config_entry.read_only)
+                                .map(|config_entry| {
+                                    let default_entry = cluster_defaults
+                                        .config_entries
+                                        .iter()
+                                        .find(|default_entry| default_entry.config_name == config_entry.config_name);
+                                    match default_entry {
+                                        Some(default_entry) => default_entry.clone(),
+                                        // the controller's own dynamic value isn't a cluster-wide default, so it's shown as unset
+                                        None if config_entry.config_source == describeconfigs_response::ConfigSource::DynamicBrokerConfig as i8 => {
+                                            let mut config_entry = config_entry.clone();
+                                            config_entry.config_value = None;
+                                            config_entry
+                                        }
+                                        None => config_entry.clone(),
+                                    }
+                                })
+                                .collect();
+                            Ok(resource)
+                        }
+                        _ => Err(ApiRequestError::from("API response missing resource info")),
+                    }
+                }
+            }
+        })
+    }))
+}
+
+/// AlterConfigs replaces every dynamic config of a resource, so the configs already set on the broker (or the
+/// cluster-wide default) have to be sent along with any change
+fn existing_broker_configs(
+    broker_info_state: &BrokerInfoState,
+    config_entry: &describeconfigs_response::ConfigEntry,
+) -> Result<Vec<alterconfigs_request::ConfigEntry>, StateFNError> {
+    let dynamic_config_source = match broker_info_state.broker_metadata {
+        Some(_) => describeconfigs_response::ConfigSource::DynamicBrokerConfig as i8,
+        None => describeconfigs_response::ConfigSource::DynamicDefaultBrokerConfig as i8,
+    };
+
+    let existing_configs = broker_info_state
+        .config_resource
+        .config_entries
+        .iter()
+        .filter(|c| c.config_source == dynamic_config_source)
+        .filter(|c| c.config_name != config_entry.config_name)
+        .collect::<Vec<&describeconfigs_response::ConfigEntry>>();
+
+    if config_entry.read_only {
+        Err(StateFNError::Error(format!("'{}' is read-only and can not be updated dynamically", config_entry.config_name)))
+    } else if let Some(sensitive_config) = existing_configs.iter().find(|c| c.is_sensitive) {
+        Err(StateFNError::Error(format!("Sensitive config '{}' is set and would be reset by this change", sensitive_config.config_name)))
+    } else {
+        Ok(existing_configs
+            .into_iter()
+            .map(|c| alterconfigs_request::ConfigEntry { config_name: c.config_name.clone(), config_value: c.config_value.clone() })
+            .collect())
+    }
+}
+
+/// Per-broker configs are altered on the broker itself, while the cluster-wide default (an empty broker name) can be
+/// altered via any broker
+fn broker_config_resource(
+    broker_info_state: &BrokerInfoState,
+    bootstrap_server: &KafkaServerAddr,
+    config_entries: Vec<alterconfigs_request::ConfigEntry>,
+) -> (KafkaServerAddr, alterconfigs_request::Resource) {
+    let (server, resource_name) = match broker_info_state.broker_metadata {
+        Some(ref broker_metadata) => {
+            (KafkaServerAddr::of(broker_metadata.host.clone(), broker_metadata.port, bootstrap_server.use_tls), broker_metadata.node_id.to_string())
+        }
+        None => (bootstrap_server.clone(), String::from("")),
+    };
+
+    (server, alterconfigs_request::Resource { resource_type: protocol_requests::ResourceTypes::Broker as i8, resource_name, config_entries })
+}
+
+fn move_selection(direction: &MoveSelection, selected_index: usize, entries_len: usize) -> usize {
+    let last_index = if entries_len > 0 { entries_len - 1 } else { 0 };
+    match direction {
+        Up => {
+            if selected_index > 0 {
+                selected_index - 1
+            } else {
+                selected_index
+            }
+        }
+        Down => {
+            if selected_index < last_index {
+                selected_index + 1
+            } else {
+                selected_index
+            }
+        }
+        PageUp => selected_index.saturating_sub(PAGE_MOVEMENT as usize),
+        PageDown => (selected_index + PAGE_MOVEMENT as usize).min(last_index),
+        Top => 0,
+        Bottom => last_index,
+        SearchNext => selected_index, // not implemented
+    }
+}
+
 fn retrieve_partition_metadata_and_offsets<T: ApiClientTrait + 'static>(
     client: IO<T, ApiRequestError>,
     bootstrap_server: &KafkaServerAddr,
     metadata_response: &metadata_response::MetadataResponse,
     topic_metadata: &metadata_response::TopicMetadata,
-) -> IO<(Vec<metadata_response::PartitionMetadata>, HashMap<i32, listoffsets_response::PartitionResponse>), ApiRequestError> {
+) -> IO<
+    (
+        Vec<metadata_response::PartitionMetadata>,
+        HashMap<i32, listoffsets_response::PartitionResponse>,
+        HashMap<i32, listoffsets_response::PartitionResponse>,
+    ),
+    ApiRequestError,
+> {
     let metadata_response = metadata_response.clone();
     let topic_metadata = topic_metadata.clone();
     let bootstrap_server = bootstrap_server.clone();
 
     client.and_then_result(Box::new(move |client: T| {
-        let partition_metadata = &topic_metadata.partition_metadata;
-        let broker_id_to_host_map = metadata_response
-            .brokers
-            .iter()
-            .map(|b| (b.node_id, KafkaServerAddr::of(b.host.clone(), b.port, bootstrap_server.use_tls)))
-            .collect::<HashMap<i32, KafkaServerAddr>>();
-
-        let mut sorted_partition_metadata = partition_metadata.clone();
+        let mut sorted_partition_metadata = topic_metadata.partition_metadata.clone();
         sorted_partition_metadata.sort_by(|a, b| a.partition.cmp(&b.partition));
 
-        let partitions_grouped_by_broker: HashMap<i32, Vec<i32>> = partition_metadata.iter().fold(HashMap::new(), |mut map, partition| {
-            let mut partitions = map.get_mut(&partition.leader).map(|vec| vec.clone()).unwrap_or(vec![]);
-            partitions.push(partition.partition);
-            map.insert(partition.leader, partitions.clone());
+        let partition_offsets =
+            |timestamp: i64| list_partition_offsets(&client, &bootstrap_server, &metadata_response.brokers, &topic_metadata, timestamp);
+
+        // -1 requests the log end offsets and -2 the log start offsets
+        partition_offsets(-1).and_then(|log_end_offsets| {
+            partition_offsets(-2).map(|log_start_offsets| (sorted_partition_metadata.clone(), log_end_offsets, log_start_offsets))
+        })
+    }))
+}
+
+fn retrieve_partition_offsets<T: ApiClientTrait + 'static>(
+    client: IO<T, ApiRequestError>,
+    bootstrap_server: &KafkaServerAddr,
+    metadata_response: &metadata_response::MetadataResponse,
+    topic_metadata: &metadata_response::TopicMetadata,
+    timestamp: i64,
+) -> IO<HashMap<i32, listoffsets_response::PartitionResponse>, ApiRequestError> {
+    let metadata_response = metadata_response.clone();
+    let topic_metadata = topic_metadata.clone();
+    let bootstrap_server = bootstrap_server.clone();
+
+    client.and_then_result(Box::new(move |client: T| {
+        list_partition_offsets(&client, &bootstrap_server, &metadata_response.brokers, &topic_metadata, timestamp)
+    }))
+}
+
+/// The offsets of every topic from the partition leaders that answered, along with the errors of those that didn't
+fn retrieve_topic_offsets<T: ApiClientTrait + 'static>(
+    client: IO<T, ApiRequestError>,
+    bootstrap_server: &KafkaServerAddr,
+    metadata_response: &metadata_response::MetadataResponse,
+    timestamp: i64,
+) -> IO<(HashMap<String, HashMap<i32, listoffsets_response::PartitionResponse>>, Vec<ApiRequestError>), ApiRequestError> {
+    let metadata_response = metadata_response.clone();
+    let bootstrap_server = bootstrap_server.clone();
+
+    client.and_then_result(Box::new(move |client: T| {
+        Ok(list_offsets(&client, &bootstrap_server, &metadata_response.brokers, &metadata_response.topic_metadata, timestamp))
+    }))
+}
+
+fn list_partition_offsets<T: ApiClientTrait>(
+    client: &T,
+    bootstrap_server: &KafkaServerAddr,
+    brokers: &Vec<metadata_response::BrokerMetadata>,
+    topic_metadata: &metadata_response::TopicMetadata,
+    timestamp: i64,
+) -> Result<HashMap<i32, listoffsets_response::PartitionResponse>, ApiRequestError> {
+    let (mut topic_offsets, errors) = list_offsets(client, bootstrap_server, brokers, &vec![topic_metadata.clone()], timestamp);
+    match (errors.into_iter().next(), topic_offsets.remove(&topic_metadata.topic)) {
+        (Some(error), _) => Err(error),
+        (None, Some(partition_offsets)) => Ok(partition_offsets),
+        (None, None) => Err(ApiRequestError::from("ListOffsets API did not return any topic offsets")),
+    }
+}
+
+/// Sends a single ListOffsets request to each partition leader, covering every topic it leads partitions for. Offline
+/// partitions are skipped, as are partitions answered with an error. The offsets from the leaders that answered are
+/// kept, along with the errors of those that didn't.
+fn list_offsets<T: ApiClientTrait>(
+    client: &T,
+    bootstrap_server: &KafkaServerAddr,
+    brokers: &Vec<metadata_response::BrokerMetadata>,
+    topic_metadata: &Vec<metadata_response::TopicMetadata>,
+    timestamp: i64,
+) -> (HashMap<String, HashMap<i32, listoffsets_response::PartitionResponse>>, Vec<ApiRequestError>) {
+    let broker_id_to_host_map = brokers
+        .iter()
+        .map(|b| (b.node_id, KafkaServerAddr::of(b.host.clone(), b.port, bootstrap_server.use_tls)))
+        .collect::<HashMap<i32, KafkaServerAddr>>();
+
+    let partitions_grouped_by_broker: HashMap<i32, HashMap<&String, Vec<i32>>> =
+        topic_metadata.iter().fold(HashMap::new(), |mut map, topic_metadata| {
+            for partition in topic_metadata.partition_metadata.iter().filter(|partition| partition.leader != -1) {
+                map.entry(partition.leader).or_insert(HashMap::new()).entry(&topic_metadata.topic).or_insert(vec![]).push(partition.partition);
+            }
             map
         });
 
-        let partition_offset_requests = partitions_grouped_by_broker
-            .iter()
-            .map(|(broker_id, partitions)| {
-                (
-                    broker_id_to_host_map.get(broker_id).unwrap_or(&bootstrap_server),
-                    listoffsets_request::Topic {
-                        topic: topic_metadata.topic.clone(),
-                        partitions: partitions.iter().map(|p| listoffsets_request::Partition { partition: *p, timestamp: -1 }).collect(),
-                    },
-                )
-            })
-            .collect::<Vec<(&KafkaServerAddr, listoffsets_request::Topic)>>();
-
-        let partition_offset_responses = partition_offset_requests
-            .into_iter()
-            .map(|(broker_address, topic)| {
-                let listoffsets_response: Result<Response<listoffsets_response::ListOffsetsResponse>, ApiRequestError> = client.request(
-                    broker_address,
-                    Request::of(listoffsets_request::ListOffsetsRequest { replica_id: -1, isolation_level: 0, topics: vec![topic] }),
-                );
-                listoffsets_response.and_then(|response| match response.response_message.responses.first() {
-                    Some(topic_offsets) => Ok(topic_offsets.partition_responses.clone()),
-                    None => Err(ApiRequestError::from("ListOffsets API did not return any topic offsets")),
+    let (responses, errors): (Vec<_>, Vec<_>) = partitions_grouped_by_broker
+        .iter()
+        .map(|(broker_id, partitions_by_topic)| {
+            let topics = partitions_by_topic
+                .iter()
+                .map(|(topic, partitions)| listoffsets_request::Topic {
+                    topic: topic.to_string(),
+                    partitions: partitions.iter().map(|p| listoffsets_request::Partition { partition: *p, timestamp }).collect(),
                 })
-            })
-            .collect::<Result<Vec<Vec<listoffsets_response::PartitionResponse>>, ApiRequestError>>()
-            .map(|vecs| vecs.flatten());
+                .collect::<Vec<listoffsets_request::Topic>>();
 
-        let partition_offsets = partition_offset_responses.map(|partition_offset_responses| {
-            partition_offset_responses
-                .into_iter()
-                .map(|partition_response| (partition_response.partition, partition_response))
-                .collect::<HashMap<i32, listoffsets_response::PartitionResponse>>()
-        });
+            let listoffsets_response: Result<Response<listoffsets_response::ListOffsetsResponse>, ApiRequestError> = client.request(
+                broker_id_to_host_map.get(broker_id).unwrap_or(bootstrap_server),
+                Request::of(listoffsets_request::ListOffsetsRequest { replica_id: -1, isolation_level: 0, topics }),
+            );
 
-        partition_offsets.map(|offsets| (sorted_partition_metadata, offsets))
-    }))
+            listoffsets_response.map(|response| {
+                response
+                    .response_message
+                    .responses
+                    .into_iter()
+                    .flat_map(|topic_offsets| {
+                        let topic = topic_offsets.topic;
+                        topic_offsets
+                            .partition_responses
+                            .into_iter()
+                            .filter(|partition_response| partition_response.error_code == 0)
+                            .map(move |partition_response| (topic.clone(), partition_response))
+                    })
+                    .collect::<Vec<(String, listoffsets_response::PartitionResponse)>>()
+            })
+        })
+        .partition(Result::is_ok);
+
+    let offsets = responses.into_iter().filter_map(Result::ok).collect::<Vec<Vec<(String, listoffsets_response::PartitionResponse)>>>().flatten();
+    let offsets = offsets.into_iter().fold(HashMap::new(), |mut map, (topic, partition_response)| {
+        map.entry(topic).or_insert(HashMap::new()).insert(partition_response.partition, partition_response);
+        map
+    });
+    (offsets, errors.into_iter().filter_map(Result::err).collect())
 }
 
 fn retrieve_consumer_offsets<T: ApiClientTrait + 'static>(
     client: IO<T, ApiRequestError>,
     group_id: &String,
     coordinator: &Coordinator,
-    topic_metadata: &metadata_response::TopicMetadata,
+    topic_metadata: &Vec<metadata_response::TopicMetadata>,
     use_tls: bool,
-) -> IO<HashMap<i32, offsetfetch_response::PartitionResponse>, ApiRequestError> {
+) -> IO<HashMap<String, HashMap<i32, offsetfetch_response::PartitionResponse>>, ApiRequestError> {
     let group_id = group_id.clone();
     let coordinator = coordinator.clone();
     let topic_metadata = topic_metadata.clone();
     let coordinator_server = KafkaServerAddr::of(coordinator.host.clone(), coordinator.port, use_tls);
 
     client.and_then_result(Box::from(move |client: T| {
-        let topic = offsetfetch_request::Topic {
-            topic: topic_metadata.topic.clone(),
-            partitions: topic_metadata.partition_metadata.iter().map(|p| p.partition).collect(),
-        };
+        let topics = topic_metadata
+            .iter()
+            .map(|topic_metadata| offsetfetch_request::Topic {
+                topic: topic_metadata.topic.clone(),
+                partitions: topic_metadata.partition_metadata.iter().map(|p| p.partition).collect(),
+            })
+            .collect::<Vec<offsetfetch_request::Topic>>();
 
         let offsetfetch_result: Result<Response<offsetfetch_response::OffsetFetchResponse>, ApiRequestError> = client
-            .request(
-                &coordinator_server,
-                Request::of(offsetfetch_request::OffsetFetchRequest { group_id: group_id.clone(), topics: vec![topic.clone()] }),
-            )
+            .request(&coordinator_server, Request::of(offsetfetch_request::OffsetFetchRequest { group_id: group_id.clone(), topics }))
             .and_then(|result: Response<offsetfetch_response::OffsetFetchResponse>| {
                 if result.response_message.error_code != 0 {
                     Err(ApiRequestError::of(format!("Error code {} with OffsetFetchRequest", result.response_message.error_code)))
@@ -739,25 +1425,89 @@ fn retrieve_consumer_offsets<T: ApiClientTrait + 'static>(
                 }
             });
 
-        let partition_responses = offsetfetch_result.and_then(|result| {
-            let responses = result
+        offsetfetch_result.map(|result| {
+            result
                 .response_message
                 .responses
                 .into_iter()
-                .find(|response| response.topic.eq(&topic.topic))
-                .map(|response| response.partition_responses);
-            match responses {
-                None => Err(ApiRequestError::from("Topic not returned from API request")),
-                Some(partition_responses) => Ok(partition_responses),
-            }
-        });
-
-        partition_responses.map(|partition_responses| {
-            partition_responses.into_iter().map(|p| (p.partition, p)).collect::<HashMap<i32, offsetfetch_response::PartitionResponse>>()
+                .map(|response| {
+                    let partition_responses = response
+                        .partition_responses
+                        .into_iter()
+                        .map(|p| (p.partition, p))
+                        .collect::<HashMap<i32, offsetfetch_response::PartitionResponse>>();
+                    (response.topic, partition_responses)
+                })
+                .collect::<HashMap<String, HashMap<i32, offsetfetch_response::PartitionResponse>>>()
         })
     }))
 }
 
+/// Total lag per topic, summed over the partitions the consumer group has committed offsets for
+fn offset_snapshot(
+    consumer_group: Option<&String>,
+    partition_offsets: &HashMap<i32, listoffsets_response::PartitionResponse>,
+    consumer_offsets: Option<&HashMap<i32, offsetfetch_response::PartitionResponse>>,
+) -> OffsetSnapshot {
+    OffsetSnapshot {
+        retrieved_ms: current_ms(),
+        consumer_group: consumer_group.cloned(),
+        partition_offsets: partition_offsets.iter().map(|(partition, partition_offset)| (*partition, partition_offset.offset)).collect(),
+        consumer_offsets: consumer_offsets
+            .map(|consumer_offsets| consumer_offsets.iter().map(|(partition, consumer_offset)| (*partition, consumer_offset.offset)).collect())
+            .unwrap_or(HashMap::new()),
+    }
+}
+
+/// The number of messages still in the log of each topic, summed across its partitions
+fn retained_messages(
+    log_end_offsets: &HashMap<String, HashMap<i32, listoffsets_response::PartitionResponse>>,
+    log_start_offsets: &HashMap<String, HashMap<i32, listoffsets_response::PartitionResponse>>,
+) -> HashMap<String, i64> {
+    log_end_offsets
+        .iter()
+        .filter_map(|(topic, log_end_offsets)| {
+            log_start_offsets.get(topic).map(|log_start_offsets| {
+                let retained = log_end_offsets
+                    .iter()
+                    .filter_map(|(partition, log_end_offset)| {
+                        log_start_offsets.get(partition).map(|log_start_offset| (log_end_offset.offset - log_start_offset.offset).max(0))
+                    })
+                    .sum::<i64>();
+                (topic.clone(), retained)
+            })
+        })
+        .collect::<HashMap<String, i64>>()
+}
+
+fn consumer_lag(
+    partition_offsets: &HashMap<String, HashMap<i32, listoffsets_response::PartitionResponse>>,
+    consumer_offsets: &HashMap<String, HashMap<i32, offsetfetch_response::PartitionResponse>>,
+) -> HashMap<String, i64> {
+    consumer_offsets
+        .iter()
+        .filter_map(|(topic, consumer_offsets)| {
+            partition_offsets.get(topic).and_then(|partition_offsets| {
+                let partition_lags = consumer_offsets
+                    .values()
+                    .filter(|consumer_offset| consumer_offset.offset >= 0)
+                    .filter_map(|consumer_offset| {
+                        partition_offsets
+                            .get(&consumer_offset.partition)
+                            .map(|partition_offset| (partition_offset.offset - consumer_offset.offset).max(0))
+                    })
+                    .collect::<Vec<i64>>();
+
+                if partition_lags.is_empty() {
+                    None
+                } else {
+                    Some((topic.clone(), partition_lags.iter().sum::<i64>()))
+                }
+            })
+        })
+        .collect::<HashMap<String, i64>>()
+}
+
 fn create_topic<T: ApiClientTrait + 'static>(
     client: IO<T, ApiRequestError>,
     topic: String,
@@ -1,5 +1,12 @@
+use std::collections::BTreeMap;
 use std::env;
 use std::io::stdin;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 
 use clap::{App, Arg};
 use regex::Regex;
@@ -23,25 +30,30 @@ use crate::kafka_protocol::protocol_requests::findcoordinator_request::Coordinat
 use crate::kafka_protocol::protocol_requests::findcoordinator_request::FindCoordinatorRequest;
 use crate::kafka_protocol::protocol_response::*;
 use crate::kafka_protocol::protocol_responses::findcoordinator_response::FindCoordinatorResponse;
+use crate::profiles::Profile;
 use crate::state::CurrentView;
 use crate::state::DialogMessage;
 use crate::user_interface::user_input;
+use crate::util::utils;
 
 pub mod error_codes;
 pub mod event_bus;
+pub mod profiles;
 pub mod state;
 pub mod user_interface;
 pub mod util;
 
 const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+const DEFAULT_REQUEST_TIMEOUT_MS: i32 = 300_000; // 5 minutes
 
 struct AppConfig<'a> {
-    bootstrap_server: KafkaServerAddr,
     consumer_group: Option<&'a str>,
     request_timeout_ms: i32,
     deletion_allowed: bool,
     deletion_confirmation: bool,
     modification_enabled: bool,
+    refresh_interval_secs: u64,
+    auto_refresh: bool,
 }
 
 fn main() -> Result<(), u8> {
@@ -49,197 +61,429 @@ fn main() -> Result<(), u8> {
 
     let matches = App::new("topiks")
         .version(VERSION)
-        .arg(Arg::with_name("bootstrap-server").required(true).takes_value(true).help("A single Kafka broker [DOMAIN|IP]:PORT"))
+        .arg(
+            Arg::with_name("bootstrap-server")
+                .required_unless("profile")
+                .takes_value(true)
+                .help("Kafka brokers [DOMAIN|IP]:PORT, comma-separated. The first one available is used"),
+        )
         .arg(Arg::with_name("tls").long("tls").required(false).help("Enable TLS"))
         .arg(Arg::with_name("consumer-group").long("consumer-group").short("c").takes_value(true).help("Consumer group for fetching offsets"))
         .arg(Arg::with_name("delete").short("D").help("Enable topic/config deletion"))
         .arg(Arg::with_name("no-delete-confirmation").long("no-delete-confirmation").help("Disable delete confirmation <Danger!>"))
         .arg(Arg::with_name("modify").short("M").help("Enable creation of topics and modification of topic configurations"))
+        .arg(
+            Arg::with_name("profile")
+                .long("profile")
+                .takes_value(true)
+                .help("Cluster profile from the config file. Other flags override the profile, and -D/-M are ignored if it disallows them"),
+        )
+        .arg(
+            Arg::with_name("config")
+                .long("config")
+                .takes_value(true)
+                .help("Config file of cluster profiles (default $XDG_CONFIG_HOME/topiks/config.toml)"),
+        )
+        .arg(
+            Arg::with_name("refresh-interval")
+                .long("refresh-interval")
+                .takes_value(true)
+                .help("Start with auto-refresh on, retrieving metadata every N seconds (default 5). Toggled with 'a'"),
+        )
         .get_matches();
 
-    let enable_tls = matches.is_present("tls");
-    let bootstrap_server =
-        KafkaServerAddr::from_arg(matches.value_of("bootstrap-server").unwrap(), enable_tls).ok_or(error_codes::COULD_NOT_PARSE_BOOTSTRAP_SERVER)?;
+    // loaded even without --profile, so that clusters can be switched to
+    let cluster_profiles = load_profiles(matches.value_of("config"), matches.is_present("profile") || matches.is_present("config"))?;
+    let profile = match matches.value_of("profile") {
+        Some(profile_name) => Some((
+            profile_name,
+            cluster_profiles.get(profile_name).ok_or_else(|| {
+                eprintln!("No profile named '{}'", profile_name);
+                error_codes::COULD_NOT_LOAD_PROFILE
+            })?,
+        )),
+        None => None,
+    };
 
-    let app_config = AppConfig {
-        bootstrap_server,
-        consumer_group: matches.value_of("consumer-group"),
-        request_timeout_ms: 300_000, // 5 minutes
-        deletion_allowed: matches.is_present("delete"),
+    let enable_tls = matches.is_present("tls") || profile.map(|(_, profile)| profile.tls).unwrap_or(false);
+    let bootstrap_servers = match matches.value_of("bootstrap-server") {
+        Some(bootstrap_servers) => bootstrap_servers.split(',').map(|bootstrap_server| bootstrap_server.trim().to_string()).collect(),
+        None => profile.map(|(_, profile)| profile.bootstrap_servers.clone()).unwrap_or(vec![]),
+    };
+    let bootstrap_servers = parse_bootstrap_servers(&bootstrap_servers, enable_tls).ok_or(error_codes::COULD_NOT_PARSE_BOOTSTRAP_SERVER)?;
+
+    let refresh_interval_secs = match matches.value_of("refresh-interval") {
+        Some(interval) => interval.parse::<u64>().ok().filter(|interval| *interval > 0).ok_or(error_codes::COULD_NOT_PARSE_REFRESH_INTERVAL)?,
+        None => 5,
+    };
+
+    let bootstrap_server = verify_bootstrap_servers(bootstrap_servers).map_err(|err| {
+        eprintln!("{}", err);
+        error_codes::KAFKA_API_VERIFICATION_FAIL
+    })?;
+
+    let mut app_config = AppConfig {
+        consumer_group: matches
+            .value_of("consumer-group")
+            .or(profile.and_then(|(_, profile)| profile.consumer_group.as_ref().map(|consumer_group| consumer_group.as_str()))),
+        request_timeout_ms: profile.and_then(|(_, profile)| profile.request_timeout_ms).unwrap_or(DEFAULT_REQUEST_TIMEOUT_MS),
+        deletion_allowed: matches.is_present("delete") && profile.and_then(|(_, profile)| profile.delete).unwrap_or(true),
         deletion_confirmation: !matches.is_present("no-delete-confirmation"),
-        modification_enabled: matches.is_present("modify"),
+        modification_enabled: matches.is_present("modify") && profile.and_then(|(_, profile)| profile.modify).unwrap_or(true),
+        refresh_interval_secs,
+        auto_refresh: matches.is_present("refresh-interval"),
     };
 
-    if let Err(err) =
-        kafka_protocol::api_verification::apply(ApiClient::new(), &app_config.bootstrap_server, &kafka_protocol::api_verification::apis_in_use())
-    {
-        eprintln!("Kafka Protocol API Error(s): {:?}", err);
-        Err(error_codes::KAFKA_API_VERIFICATION_FAIL)
-    } else {
-        let sender = event_bus::start();
-        let _stdout = &mut AlternateScreen::from(std::io::stdout().into_raw_mode().unwrap()); // raw mode to avoid screen output
-        let stdin = stdin();
-
-        let bootstrap_server = || app_config.bootstrap_server.clone();
-
-        let consumer_group = app_config.consumer_group.clone().and_then(|cg| {
-            let find_coordinator_response: Result<Response<FindCoordinatorResponse>, ApiRequestError> = ApiClient::new().request(
-                &app_config.bootstrap_server,
-                Request::of(FindCoordinatorRequest { coordinator_key: String::from(cg), coordinator_type: CoordinatorType::Group as i8 }),
-            );
-            find_coordinator_response.ok().and_then(|find_coordinator_response| {
-                if find_coordinator_response.response_message.error_code == 0 {
-                    Some(ConsumerGroup(String::from(cg), find_coordinator_response.response_message.coordinator))
-                } else {
-                    sender
-                        .send(Message::DisplayUIMessage(DialogMessage::Error(format!("Could not determine coordinator for consumer group {}", cg))))
-                        .unwrap();
-                    None
-                }
-            })
-        });
+    let last_live_server = Arc::new(Mutex::new(None));
+    let sender = event_bus::start(last_live_server.clone());
+    let _stdout = &mut AlternateScreen::from(std::io::stdout().into_raw_mode().unwrap()); // raw mode to avoid screen output
+    let stdin = stdin();
 
-        sender.send(Message::GetMetadata(bootstrap_server(), consumer_group.clone())).unwrap();
+    // shared with the auto-refresh thread, and replaced when switching clusters
+    let cluster_bootstrap_server = Arc::new(Mutex::new(bootstrap_server));
+    let bootstrap_server = || cluster_bootstrap_server.lock().unwrap().clone();
+    // the broker metadata was last retrieved from, which can differ from the bootstrap server after a failover
+    let live_server = || last_live_server.lock().unwrap().clone().unwrap_or(bootstrap_server());
+    let consumer_group = Arc::new(Mutex::new(app_config.consumer_group.clone().and_then(|cg| find_consumer_group(&live_server(), cg, &sender))));
+    let current_consumer_group = || consumer_group.lock().unwrap().clone();
+    let mut known_consumer_groups = app_config.consumer_group.iter().map(|cg| cg.to_string()).collect::<Vec<String>>();
 
-        let create_topic_regex = Regex::new(r"^[A-Za-z0-9\._]{1, 249}:[0-9]{1,3}:[0-9]{1,3}$").expect("Could not compile regex for creating topics");
+    let auto_refresh = Arc::new(AtomicBool::new(app_config.auto_refresh));
+    start_auto_refresh(&app_config, auto_refresh.clone(), cluster_bootstrap_server.clone(), consumer_group.clone(), sender.clone());
+    if app_config.auto_refresh {
+        sender.send(Message::SetAutoRefresh(Some(app_config.refresh_interval_secs))).unwrap();
+    }
+    if let Some((profile_name, profile)) = profile {
+        sender.send(Message::SwitchCluster(profile.cluster(profile_name))).unwrap();
+    }
 
-        for key in stdin.keys() {
-            match key.unwrap() {
-                Key::Char('h') => {
-                    sender.send(Message::ToggleView(CurrentView::HelpScreen)).unwrap();
-                }
-                Key::Char('q') => match sender.send(Message::Quit) {
-                    Ok(_) => break,
-                    Err(_) => {
-                        eprintln!("Failed to signal event bus/user interface thread. Exiting now");
-                        break;
-                    }
-                },
-                Key::Char('r') => {
-                    sender.send(Message::DisplayUIMessage(DialogMessage::None)).unwrap();
-                    sender.send(Message::GetMetadata(bootstrap_server(), consumer_group.clone())).unwrap();
+    sender.send(Message::GetMetadata(bootstrap_server(), current_consumer_group())).unwrap();
+
+    let create_topic_regex = Regex::new(r"^[A-Za-z0-9\._]{1, 249}:[0-9]{1,3}:[0-9]{1,3}$").expect("Could not compile regex for creating topics");
+
+    for key in stdin.keys() {
+        match key.unwrap() {
+            Key::Char('h') => {
+                sender.send(Message::ToggleView(CurrentView::HelpScreen)).unwrap();
+            }
+            Key::Char('q') => match sender.send(Message::Quit) {
+                Ok(_) => break,
+                Err(_) => {
+                    eprintln!("Failed to signal event bus/user interface thread. Exiting now");
+                    break;
                 }
-                Key::Char('c') => {
-                    if app_config.modification_enabled {
-                        let (_, height) = terminal_size().unwrap();
-                        match user_input::read("▶︎ ", (1, height), sender.clone()) {
-                            Ok(Some(ref input)) if create_topic_regex.is_match(input.as_str()) => {
-                                match input.split(":").collect::<Vec<&str>>().as_slice() {
-                                    &[topic, partitions, replication_factor] => {
-                                        let create_topic = partitions.to_string().parse::<i32>().and_then(|partitions| {
-                                            replication_factor.to_string().parse::<i16>().map(|replication_factor| Creation::Topic {
-                                                name: topic.to_string(),
-                                                partitions,
-                                                replication_factor,
-                                            })
-                                        });
-                                        match create_topic {
-                                            Ok(create_topic) => {
-                                                sender.send(Message::Create(bootstrap_server(), create_topic, app_config.request_timeout_ms)).unwrap()
-                                            }
-                                            Err(_) => sender
-                                                .send(Message::DisplayUIMessage(DialogMessage::Error("Invalid input for creating topic".to_string())))
-                                                .unwrap(),
+            },
+            Key::Char('r') => {
+                sender.send(Message::DisplayUIMessage(DialogMessage::None)).unwrap();
+                sender.send(Message::GetMetadata(bootstrap_server(), current_consumer_group())).unwrap();
+            }
+            Key::Char('c') => {
+                if app_config.modification_enabled {
+                    let (_, height) = terminal_size().unwrap();
+                    match user_input::read("▶︎ ", (1, height), sender.clone()) {
+                        Ok(Some(ref input)) if create_topic_regex.is_match(input.as_str()) => {
+                            match input.split(":").collect::<Vec<&str>>().as_slice() {
+                                &[topic, partitions, replication_factor] => {
+                                    let create_topic = partitions.to_string().parse::<i32>().and_then(|partitions| {
+                                        replication_factor.to_string().parse::<i16>().map(|replication_factor| Creation::Topic {
+                                            name: topic.to_string(),
+                                            partitions,
+                                            replication_factor,
+                                        })
+                                    });
+                                    match create_topic {
+                                        Ok(create_topic) => {
+                                            sender.send(Message::Create(bootstrap_server(), create_topic, app_config.request_timeout_ms)).unwrap()
                                         }
+                                        Err(_) => sender
+                                            .send(Message::DisplayUIMessage(DialogMessage::Error("Invalid input for creating topic".to_string())))
+                                            .unwrap(),
                                     }
-                                    _ => sender
-                                        .send(Message::DisplayUIMessage(DialogMessage::Error("Invalid input for creating topic".to_string())))
-                                        .unwrap(),
                                 }
+                                _ => sender
+                                    .send(Message::DisplayUIMessage(DialogMessage::Error("Invalid input for creating topic".to_string())))
+                                    .unwrap(),
                             }
-                            _ => sender
-                                .send(Message::DisplayUIMessage(DialogMessage::Error(
-                                    "Input should be [topic]:[partitions]:[replication factor]".to_string(),
-                                )))
-                                .unwrap(),
                         }
+                        _ => sender
+                            .send(Message::DisplayUIMessage(DialogMessage::Error(
+                                "Input should be [topic]:[partitions]:[replication factor]".to_string(),
+                            )))
+                            .unwrap(),
                     }
                 }
-                Key::Char('d') => {
-                    if app_config.deletion_allowed {
-                        sender.send(Message::DisplayUIMessage(DialogMessage::Warn(format!("Deleting...")))).unwrap();
-                        if app_config.deletion_confirmation {
-                            let (_, height) = terminal_size().unwrap();
-                            match user_input::read("[Yes]?: ", (1, height), sender.clone()) {
-                                Ok(Some(confirm)) => {
-                                    if confirm.eq("Yes") {
-                                        sender.send(Message::Delete(bootstrap_server(), app_config.request_timeout_ms)).unwrap();
-                                    } else {
-                                        sender.send(Message::Noop).unwrap();
-                                    }
+            }
+            Key::Char('d') => {
+                if app_config.deletion_allowed {
+                    sender.send(Message::DisplayUIMessage(DialogMessage::Warn(format!("Deleting...")))).unwrap();
+                    if app_config.deletion_confirmation {
+                        let (_, height) = terminal_size().unwrap();
+                        match user_input::read("[Yes]?: ", (1, height), sender.clone()) {
+                            Ok(Some(confirm)) => {
+                                if confirm.eq("Yes") {
+                                    sender.send(Message::Delete(bootstrap_server(), app_config.request_timeout_ms)).unwrap();
+                                } else {
+                                    sender.send(Message::Noop).unwrap();
                                 }
-                                _ => (),
                             }
-                        } else {
-                            sender.send(Message::Delete(bootstrap_server(), app_config.request_timeout_ms)).unwrap();
+                            _ => (),
                         }
-                        sender.send(Message::DisplayUIMessage(DialogMessage::None)).unwrap();
+                    } else {
+                        sender.send(Message::Delete(bootstrap_server(), app_config.request_timeout_ms)).unwrap();
                     }
-                }
-                Key::Up => {
-                    sender.send(Message::Select(Up)).unwrap();
-                }
-                Key::PageUp => {
-                    sender.send(Message::Select(PageUp)).unwrap();
-                }
-                Key::Down => {
-                    sender.send(Message::Select(Down)).unwrap();
-                }
-                Key::PageDown => {
-                    sender.send(Message::Select(PageDown)).unwrap();
-                }
-                Key::Home => {
-                    sender.send(Message::Select(Top)).unwrap();
-                }
-                Key::End => {
-                    sender.send(Message::Select(Bottom)).unwrap();
-                }
-                Key::Char('t') => {
                     sender.send(Message::DisplayUIMessage(DialogMessage::None)).unwrap();
-                    sender.send(Message::ToggleView(CurrentView::Topics)).unwrap();
-                    sender.send(Message::GetMetadata(bootstrap_server(), consumer_group.clone())).unwrap();
                 }
-                Key::Char('i') => {
-                    sender.send(Message::DisplayUIMessage(DialogMessage::None)).unwrap();
-                    sender.send(Message::ToggleView(CurrentView::TopicInfo)).unwrap();
-                    sender.send(Message::GetMetadata(bootstrap_server(), consumer_group.clone())).unwrap();
+            }
+            Key::Up => {
+                sender.send(Message::Select(Up)).unwrap();
+            }
+            Key::PageUp => {
+                sender.send(Message::Select(PageUp)).unwrap();
+            }
+            Key::Down => {
+                sender.send(Message::Select(Down)).unwrap();
+            }
+            Key::PageDown => {
+                sender.send(Message::Select(PageDown)).unwrap();
+            }
+            Key::Home => {
+                sender.send(Message::Select(Top)).unwrap();
+            }
+            Key::End => {
+                sender.send(Message::Select(Bottom)).unwrap();
+            }
+            Key::Char('t') => {
+                sender.send(Message::DisplayUIMessage(DialogMessage::None)).unwrap();
+                sender.send(Message::ToggleView(CurrentView::Topics)).unwrap();
+                sender.send(Message::GetMetadata(bootstrap_server(), current_consumer_group())).unwrap();
+            }
+            Key::Char('i') => {
+                sender.send(Message::DisplayUIMessage(DialogMessage::None)).unwrap();
+                sender.send(Message::ToggleView(CurrentView::TopicInfo)).unwrap();
+                sender.send(Message::GetMetadata(bootstrap_server(), current_consumer_group())).unwrap();
+            }
+            Key::Char('p') => {
+                sender.send(Message::DisplayUIMessage(DialogMessage::None)).unwrap();
+                sender.send(Message::ToggleView(CurrentView::Partitions)).unwrap();
+                sender.send(Message::GetMetadata(bootstrap_server(), current_consumer_group())).unwrap();
+            }
+            Key::Char('b') => {
+                sender.send(Message::DisplayUIMessage(DialogMessage::None)).unwrap();
+                sender.send(Message::ToggleView(CurrentView::Brokers)).unwrap();
+                sender.send(Message::GetMetadata(bootstrap_server(), current_consumer_group())).unwrap();
+            }
+            Key::Char('B') => {
+                sender.send(Message::DisplayUIMessage(DialogMessage::None)).unwrap();
+                sender.send(Message::ToggleView(CurrentView::BrokerInfo)).unwrap();
+                sender.send(Message::GetMetadata(bootstrap_server(), current_consumer_group())).unwrap();
+            }
+            Key::Char('u') => {
+                sender.send(Message::DisplayUIMessage(DialogMessage::None)).unwrap();
+                sender.send(Message::ToggleView(CurrentView::Health)).unwrap();
+                sender.send(Message::GetMetadata(bootstrap_server(), current_consumer_group())).unwrap();
+            }
+            Key::Char('a') => {
+                let enabled = !auto_refresh.load(Ordering::SeqCst);
+                auto_refresh.store(enabled, Ordering::SeqCst);
+                let interval_secs = if enabled { Some(app_config.refresh_interval_secs) } else { None };
+                sender.send(Message::SetAutoRefresh(interval_secs)).unwrap();
+            }
+            Key::Char('g') => {
+                let (_width, height) = terminal_size().unwrap();
+                match user_input::read_with_completion("group: ", (1, height), sender.clone(), &known_consumer_groups) {
+                    Ok(Some(group_id)) => {
+                        if let Some(selected_group) = find_consumer_group(&live_server(), group_id.as_str(), &sender) {
+                            if !known_consumer_groups.contains(&group_id) {
+                                known_consumer_groups.push(group_id.clone());
+                            }
+                            *consumer_group.lock().unwrap() = Some(selected_group);
+                            sender.send(Message::GetMetadata(bootstrap_server(), current_consumer_group())).unwrap();
+                            sender.send(Message::DisplayUIMessage(DialogMessage::Info(format!("Consumer group set to '{}'", group_id)))).unwrap();
+                        }
+                    }
+                    Ok(None) => {
+                        *consumer_group.lock().unwrap() = None;
+                        sender.send(Message::GetMetadata(bootstrap_server(), current_consumer_group())).unwrap();
+                        sender.send(Message::DisplayUIMessage(DialogMessage::Info(format!("Consumer group unset")))).unwrap();
+                    }
+                    Err(_) => (),
                 }
-                Key::Char('p') => {
-                    sender.send(Message::DisplayUIMessage(DialogMessage::None)).unwrap();
-                    sender.send(Message::ToggleView(CurrentView::Partitions)).unwrap();
-                    sender.send(Message::GetMetadata(bootstrap_server(), consumer_group.clone())).unwrap();
+            }
+            Key::Char('o') => {
+                let (_width, height) = terminal_size().unwrap();
+                match user_input::read("offsets at: ", (1, height), sender.clone()) {
+                    Ok(Some(input)) => match utils::parse_timestamp(&input) {
+                        Some(timestamp) => sender.send(Message::LookupOffsets(bootstrap_server(), timestamp)).unwrap(),
+                        None => sender
+                            .send(Message::DisplayUIMessage(DialogMessage::Error(format!("Could not parse '{}' as ISO-8601 or epoch millis", input))))
+                            .unwrap(),
+                    },
+                    _ => (),
                 }
-                Key::Char('/') => {
-                    sender.send(Message::DisplayUIMessage(DialogMessage::Info(format!("Search")))).unwrap();
+            }
+            Key::Char('C') => {
+                let profile_names = cluster_profiles.keys().cloned().collect::<Vec<String>>();
+                if profile_names.is_empty() {
+                    sender.send(Message::DisplayUIMessage(DialogMessage::Error("No cluster profiles configured".to_string()))).unwrap();
+                } else {
+                    sender.send(Message::DisplayUIMessage(DialogMessage::Info(format!("Clusters: {}", profile_names.join(", "))))).unwrap();
                     let (_width, height) = terminal_size().unwrap();
-                    let query = match user_input::read("/", (1, height), sender.clone()) {
-                        Ok(Some(query)) => Message::SetTopicQuery(Query(query)),
-                        Ok(None) => Message::SetTopicQuery(NoQuery),
-                        Err(_) => Message::SetTopicQuery(NoQuery),
-                    };
-                    sender.send(query).unwrap();
-                    sender.send(Message::Select(SearchNext)).unwrap();
-                    sender.send(Message::DisplayUIMessage(DialogMessage::None)).unwrap();
-                }
-                Key::Char('n') => {
-                    // TODO support Shift+n for reverse
-                    sender.send(Message::Select(SearchNext)).unwrap();
-                }
-                Key::Char(':') => {
-                    if app_config.modification_enabled {
-                        let (_width, height) = terminal_size().unwrap();
-                        match user_input::read(":", (1, height), sender.clone()) {
-                            Ok(modify_value) => {
-                                sender.send(Message::ModifyValue(bootstrap_server(), modify_value)).unwrap();
+                    match user_input::read_with_completion("cluster: ", (1, height), sender.clone(), &profile_names) {
+                        Ok(Some(profile_name)) => match cluster_profiles.get(&profile_name) {
+                            Some(profile) => {
+                                sender.send(Message::DisplayUIMessage(DialogMessage::Warn(format!("Connecting to {}...", profile_name)))).unwrap();
+                                let verified_server = parse_bootstrap_servers(&profile.bootstrap_servers, profile.tls)
+                                    .ok_or(format!("Could not parse the bootstrap servers of {}", profile_name))
+                                    .and_then(verify_bootstrap_servers);
+                                match verified_server {
+                                    Ok(verified_server) => {
+                                        // the live server is still a broker of the previous cluster until the switch is handled
+                                        let profile_consumer_group =
+                                            profile.consumer_group.as_ref().and_then(|cg| find_consumer_group(&verified_server, cg, &sender));
+                                        *cluster_bootstrap_server.lock().unwrap() = verified_server;
+                                        *consumer_group.lock().unwrap() = profile_consumer_group;
+                                        if let Some(ref group_id) = profile.consumer_group {
+                                            if !known_consumer_groups.contains(group_id) {
+                                                known_consumer_groups.push(group_id.clone());
+                                            }
+                                        }
+                                        app_config.request_timeout_ms = profile.request_timeout_ms.unwrap_or(DEFAULT_REQUEST_TIMEOUT_MS);
+                                        app_config.deletion_allowed = matches.is_present("delete") && profile.delete.unwrap_or(true);
+                                        app_config.modification_enabled = matches.is_present("modify") && profile.modify.unwrap_or(true);
+
+                                        sender.send(Message::SwitchCluster(profile.cluster(&profile_name))).unwrap();
+                                        sender.send(Message::GetMetadata(bootstrap_server(), current_consumer_group())).unwrap();
+                                    }
+                                    Err(err) => sender.send(Message::DisplayUIMessage(DialogMessage::Error(err))).unwrap(),
+                                }
                             }
-                            _ => (),
+                            None => sender
+                                .send(Message::DisplayUIMessage(DialogMessage::Error(format!("No cluster profile named '{}'", profile_name))))
+                                .unwrap(),
+                        },
+                        _ => sender.send(Message::DisplayUIMessage(DialogMessage::None)).unwrap(),
+                    }
+                }
+            }
+            Key::Char('/') => {
+                sender.send(Message::DisplayUIMessage(DialogMessage::Info(format!("Search")))).unwrap();
+                let (_width, height) = terminal_size().unwrap();
+                let query = match user_input::read("/", (1, height), sender.clone()) {
+                    Ok(Some(query)) => Message::SetTopicQuery(Query(query)),
+                    Ok(None) => Message::SetTopicQuery(NoQuery),
+                    Err(_) => Message::SetTopicQuery(NoQuery),
+                };
+                sender.send(query).unwrap();
+                sender.send(Message::Select(SearchNext)).unwrap();
+                sender.send(Message::DisplayUIMessage(DialogMessage::None)).unwrap();
+            }
+            Key::Char('n') => {
+                // TODO support Shift+n for reverse
+                sender.send(Message::Select(SearchNext)).unwrap();
+            }
+            Key::Char(':') => {
+                if app_config.modification_enabled {
+                    let (_width, height) = terminal_size().unwrap();
+                    match user_input::read(":", (1, height), sender.clone()) {
+                        Ok(modify_value) => {
+                            sender.send(Message::ModifyValue(bootstrap_server(), modify_value)).unwrap();
                         }
+                        _ => (),
                     }
                 }
-                _ => {}
             }
+            _ => {}
         }
+    }
+
+    Ok(())
+}
+
+/// A missing or invalid config file is only an error when it is required, i.e. a profile or config file was asked for
+fn load_profiles(config_path: Option<&str>, required: bool) -> Result<BTreeMap<String, Profile>, u8> {
+    match config_path.map(PathBuf::from).or_else(profiles::default_path) {
+        Some(ref config_path) if required => profiles::load(config_path).map_err(|err| {
+            eprintln!("{}", err);
+            error_codes::COULD_NOT_LOAD_PROFILE
+        }),
+        Some(ref config_path) if config_path.exists() => Ok(profiles::load(config_path).unwrap_or_else(|err| {
+            eprintln!("{}, skipping its cluster profiles", err);
+            BTreeMap::new()
+        })),
+        Some(_) => Ok(BTreeMap::new()),
+        None if required => Err(error_codes::COULD_NOT_LOAD_PROFILE),
+        None => Ok(BTreeMap::new()),
+    }
+}
+
+fn parse_bootstrap_servers(bootstrap_servers: &Vec<String>, use_tls: bool) -> Option<Vec<KafkaServerAddr>> {
+    bootstrap_servers
+        .iter()
+        .map(|bootstrap_server| KafkaServerAddr::from_arg(bootstrap_server, use_tls))
+        .collect::<Option<Vec<KafkaServerAddr>>>()
+        .filter(|bootstrap_servers| !bootstrap_servers.is_empty())
+}
 
-        Ok(())
+/// The first of the bootstrap servers that passes API verification
+fn verify_bootstrap_servers(bootstrap_servers: Vec<KafkaServerAddr>) -> Result<KafkaServerAddr, String> {
+    let mut verification_errors = vec![];
+    let verified_server = bootstrap_servers.into_iter().find(|bootstrap_server| {
+        match kafka_protocol::api_verification::apply(ApiClient::new(), bootstrap_server, &kafka_protocol::api_verification::apis_in_use()) {
+            Ok(_) => true,
+            Err(err) => {
+                verification_errors.push(format!("{:?}", err));
+                false
+            }
+        }
+    });
+    verified_server.ok_or(format!("Kafka Protocol API Error(s): {}", verification_errors.join(", ")))
+}
+
+/// Keeps retrieving metadata for the current view while auto-refresh is enabled
+fn start_auto_refresh(
+    app_config: &AppConfig,
+    auto_refresh: Arc<AtomicBool>,
+    bootstrap_server: Arc<Mutex<KafkaServerAddr>>,
+    consumer_group: Arc<Mutex<Option<ConsumerGroup>>>,
+    sender: Sender<Message>,
+) {
+    let refresh_interval = Duration::from_secs(app_config.refresh_interval_secs);
+    let refreshing = Arc::new(AtomicBool::new(false)); // until the event bus has handled the last refresh
+
+    thread::spawn(move || loop {
+        thread::sleep(refresh_interval);
+        // skips the tick while the last refresh is still running, so refreshes don't pile up on a slow cluster
+        if auto_refresh.load(Ordering::SeqCst) && !refreshing.swap(true, Ordering::SeqCst) {
+            let bootstrap_server = bootstrap_server.lock().unwrap().clone();
+            let consumer_group = consumer_group.lock().unwrap().clone();
+            if sender.send(Message::RefreshMetadata(bootstrap_server, consumer_group, refreshing.clone())).is_err() {
+                break; // event bus has stopped
+            }
+        }
+    });
+}
+
+fn find_consumer_group(bootstrap_server: &KafkaServerAddr, group_id: &str, sender: &Sender<Message>) -> Option<ConsumerGroup> {
+    let find_coordinator_response: Result<Response<FindCoordinatorResponse>, ApiRequestError> = ApiClient::new().request(
+        bootstrap_server,
+        Request::of(FindCoordinatorRequest { coordinator_key: String::from(group_id), coordinator_type: CoordinatorType::Group as i8 }),
+    );
+    match find_coordinator_response {
+        Ok(find_coordinator_response) => {
+            if find_coordinator_response.response_message.error_code == 0 {
+                Some(ConsumerGroup(String::from(group_id), find_coordinator_response.response_message.coordinator))
+            } else {
+                sender
+                    .send(Message::DisplayUIMessage(DialogMessage::Error(format!("Could not determine coordinator for consumer group {}", group_id))))
+                    .unwrap();
+                None
+            }
+        }
+        Err(err) => {
+            sender
+                .send(Message::DisplayUIMessage(DialogMessage::Error(format!("Could not find coordinator for consumer group {}: {}", group_id, err))))
+                .unwrap();
+            None
+        }
     }
 }
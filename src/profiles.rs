@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::state::Cluster;
+
+/// A named cluster from the config file, e.g.
+///
+/// ```toml
+/// [profiles.staging]
+/// bootstrap_servers = ["kafka-1:9092", "kafka-2:9092"]
+/// tls = true
+/// consumer_group = "my-app"
+/// request_timeout_ms = 60000
+/// delete = false
+/// modify = true
+/// color = "yellow"
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+pub struct Profile {
+    pub bootstrap_servers: Vec<String>,
+    #[serde(default)]
+    pub tls: bool,
+    pub consumer_group: Option<String>,
+    pub request_timeout_ms: Option<i32>,
+    pub delete: Option<bool>,  // whether -D is allowed, it still has to be given
+    pub modify: Option<bool>,  // whether -M is allowed, it still has to be given
+    pub color: Option<String>, // of the cluster name in the header, e.g. red for prod
+}
+
+impl Profile {
+    pub fn cluster(&self, name: &str) -> Cluster {
+        Cluster { name: name.to_string(), color: self.color.clone() }
+    }
+}
+
+#[derive(Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    profiles: BTreeMap<String, Profile>,
+}
+
+/// $XDG_CONFIG_HOME/topiks/config.toml, falling back to ~/.config/topiks/config.toml
+pub fn default_path() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .filter(|config_home| !config_home.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|config_home| config_home.join("topiks").join("config.toml"))
+}
+
+/// The profiles of the config file, sorted by name
+pub fn load(path: &Path) -> Result<BTreeMap<String, Profile>, String> {
+    fs::read_to_string(path)
+        .map_err(|err| format!("Could not read config file {}: {}", path.display(), err))
+        .and_then(|contents| parse(&contents).map_err(|err| format!("Could not parse config file {}: {}", path.display(), err)))
+}
+
+pub fn parse(contents: &str) -> Result<BTreeMap<String, Profile>, String> {
+    toml::from_str::<ConfigFile>(contents).map(|config_file| config_file.profiles).map_err(|err| format!("{}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_profiles() {
+        let profiles = parse(
+            r#"
+            [profiles.dev]
+            bootstrap_servers = ["localhost:9092"]
+
+            [profiles.staging]
+            bootstrap_servers = ["kafka-1:9093", "kafka-2:9093"]
+            tls = true
+            consumer_group = "my-app"
+            request_timeout_ms = 60000
+            delete = false
+            "#,
+        )
+        .expect("Should parse profiles");
+
+        assert_eq!(profiles.keys().collect::<Vec<&String>>(), vec!["dev", "staging"]);
+        let dev = &profiles["dev"];
+        assert!(!dev.tls);
+        assert_eq!(dev.consumer_group, None);
+        assert_eq!(dev.delete, None);
+        let staging = &profiles["staging"];
+        assert_eq!(staging.bootstrap_servers, vec!["kafka-1:9093", "kafka-2:9093"]);
+        assert!(staging.tls);
+        assert_eq!(staging.consumer_group, Some(String::from("my-app")));
+        assert_eq!(staging.request_timeout_ms, Some(60000));
+        assert_eq!(staging.delete, Some(false));
+        assert_eq!(staging.modify, None);
+
+        assert!(parse("[profiles.broken]\ntls = true").is_err()); // no bootstrap servers
+    }
+}
@@ -1,13 +1,16 @@
+use std::cmp;
 use std::collections::HashMap;
 use std::fmt::Display;
 
 use crate::kafka_protocol::protocol_responses::describeconfigs_response::Resource;
 use crate::kafka_protocol::protocol_responses::listoffsets_response;
+use crate::kafka_protocol::protocol_responses::metadata_response::BrokerMetadata;
 use crate::kafka_protocol::protocol_responses::metadata_response::MetadataResponse;
 use crate::kafka_protocol::protocol_responses::metadata_response::PartitionMetadata;
 use crate::kafka_protocol::protocol_responses::metadata_response::TopicMetadata;
 use crate::kafka_protocol::protocol_responses::offsetfetch_response;
 use crate::state::CurrentView::*;
+use crate::KafkaServerAddr;
 
 #[derive(Clone)]
 pub struct State {
@@ -20,6 +23,25 @@ pub struct State {
     pub topic_name_query: Option<String>,
     pub topic_info_state: Option<TopicInfoState>,
     pub partition_info_state: Option<PartitionInfoState>,
+    pub topic_consumer_lag: HashMap<String, i64>,
+    pub topic_retained_messages: HashMap<String, i64>,
+    pub selected_broker_index: usize,
+    pub broker_info_state: Option<BrokerInfoState>,
+    pub topic_min_isr: Option<HashMap<String, i32>>, // None when min.insync.replicas could not be described
+    pub topic_details_retrieved_ms: Option<u64>,     // when the retained message counts and min ISR of all topics were last retrieved
+    pub selected_health_index: usize,
+    pub offset_snapshots: HashMap<String, OffsetSnapshot>,
+    pub offset_rates: HashMap<String, HashMap<i32, OffsetRates>>,
+    pub last_refreshed_ms: Option<u64>,
+    pub live_server: Option<KafkaServerAddr>, // the broker metadata was last retrieved from
+    pub auto_refresh_interval_secs: Option<u64>,
+    pub cluster: Option<Cluster>, // the profile connected to, if any
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cluster {
+    pub name: String,
+    pub color: Option<String>,
 }
 
 #[derive(Clone)]
@@ -35,6 +57,9 @@ pub enum CurrentView {
     Topics,
     Partitions,
     TopicInfo,
+    Brokers,
+    BrokerInfo,
+    Health,
     HelpScreen,
 }
 
@@ -66,6 +91,19 @@ impl State {
             topic_name_query: None,
             topic_info_state: None,
             partition_info_state: None,
+            topic_consumer_lag: HashMap::new(),
+            topic_retained_messages: HashMap::new(),
+            selected_broker_index: 0,
+            broker_info_state: None,
+            topic_min_isr: None,
+            topic_details_retrieved_ms: None,
+            selected_health_index: 0,
+            offset_snapshots: HashMap::new(),
+            offset_rates: HashMap::new(),
+            last_refreshed_ms: None,
+            live_server: None,
+            auto_refresh_interval_secs: None,
+            cluster: None,
         }
     }
 
@@ -77,6 +115,50 @@ impl State {
         self.metadata.as_ref().and_then(|metadata| metadata.topic_metadata.get(self.selected_index).map(|topic_metadata| topic_metadata.clone()))
     }
 
+    pub fn selected_broker_metadata(&self) -> Option<BrokerMetadata> {
+        self.metadata.as_ref().and_then(|metadata| metadata.brokers.get(self.selected_broker_index).map(|broker_metadata| broker_metadata.clone()))
+    }
+
+    pub fn selected_cluster_default(&self) -> bool {
+        self.metadata.as_ref().map(|metadata| self.selected_broker_index == metadata.brokers.len()).unwrap_or(false)
+    }
+
+    /// Replaces the metadata, keeping the same topic and broker selected even when their positions changed
+    pub fn set_metadata(&mut self, metadata: MetadataResponse) {
+        let selected_topic_name = self.selected_topic_name();
+        let selected_node_id = self.selected_broker_metadata().map(|broker_metadata| broker_metadata.node_id);
+
+        self.selected_index = selected_topic_name
+            .and_then(|topic_name| metadata.topic_metadata.iter().position(|topic_metadata| topic_metadata.topic == topic_name))
+            .unwrap_or(clamp_index(self.selected_index, metadata.topic_metadata.len()));
+        self.selected_broker_index = if self.selected_cluster_default() {
+            metadata.brokers.len()
+        } else {
+            selected_node_id
+                .and_then(|node_id| metadata.brokers.iter().position(|broker_metadata| broker_metadata.node_id == node_id))
+                .unwrap_or(clamp_index(self.selected_broker_index, metadata.brokers.len() + 1))
+            // the cluster-wide defaults follow the brokers
+        };
+        self.marked_deleted.retain(|topic| metadata.topic_metadata.iter().any(|topic_metadata| &topic_metadata.topic == topic));
+        self.metadata = Some(metadata);
+    }
+
+    /// Keeps the latest offsets of each topic, estimating rates against the offsets of the previous refresh
+    pub fn record_offset_snapshots(&mut self, offset_snapshots: HashMap<String, OffsetSnapshot>) {
+        for (topic, offset_snapshot) in offset_snapshots {
+            match self.offset_snapshots.get(&topic).map(|previous| offset_snapshot.rates_since(previous)) {
+                Some(ref rates) if rates.is_empty() => (), // e.g. refreshed within the same millisecond
+                Some(rates) => {
+                    self.offset_rates.insert(topic.clone(), rates);
+                }
+                None => {
+                    self.offset_rates.remove(&topic);
+                }
+            }
+            self.offset_snapshots.insert(topic, offset_snapshot);
+        }
+    }
+
     pub fn find_next_index(&self, in_reverse: bool) -> Option<usize> {
         self.topic_name_query.as_ref().and_then(|query| {
             self.metadata.as_ref().and_then(|metadata| {
@@ -118,22 +200,150 @@ impl TopicInfoState {
     pub fn new(topic_metadata: TopicMetadata, config_resource: Resource) -> TopicInfoState {
         TopicInfoState { topic_metadata, config_resource, selected_index: 0, configs_marked_deleted: vec![], configs_marked_modified: vec![] }
     }
+
+    /// Keeps the selection and marks of the previous state when it was for the same topic
+    pub fn retain_selection(mut self, previous: &Option<TopicInfoState>) -> TopicInfoState {
+        if let Some(previous) = previous.as_ref().filter(|previous| previous.topic_metadata.topic == self.topic_metadata.topic) {
+            self.selected_index = clamp_index(previous.selected_index, self.config_resource.config_entries.len());
+            self.configs_marked_deleted = previous.configs_marked_deleted.clone();
+            self.configs_marked_modified = previous.configs_marked_modified.clone();
+        }
+        self
+    }
 }
 
 #[derive(Clone)]
 pub struct PartitionInfoState {
+    pub topic: String,
     pub selected_index: usize,
     pub partition_metadata: Vec<PartitionMetadata>,
     pub partition_offsets: HashMap<i32, listoffsets_response::PartitionResponse>,
+    pub log_start_offsets: HashMap<i32, listoffsets_response::PartitionResponse>,
     pub consumer_offsets: HashMap<i32, offsetfetch_response::PartitionResponse>,
+    pub timestamp_offsets: Option<TimestampOffsets>,
+}
+
+/// The offsets of each partition at a point in time, as looked up by timestamp
+#[derive(Clone)]
+pub struct TimestampOffsets {
+    pub timestamp: i64,
+    pub offsets: HashMap<i32, listoffsets_response::PartitionResponse>,
 }
 
 impl PartitionInfoState {
     pub fn new(
+        topic: String,
         partition_metadata: Vec<PartitionMetadata>,
         partition_offsets: HashMap<i32, listoffsets_response::PartitionResponse>,
+        log_start_offsets: HashMap<i32, listoffsets_response::PartitionResponse>,
         consumer_offsets: HashMap<i32, offsetfetch_response::PartitionResponse>,
     ) -> PartitionInfoState {
-        PartitionInfoState { selected_index: 0, partition_metadata, partition_offsets, consumer_offsets }
+        PartitionInfoState {
+            topic,
+            selected_index: 0,
+            partition_metadata,
+            partition_offsets,
+            log_start_offsets,
+            consumer_offsets,
+            timestamp_offsets: None,
+        }
+    }
+
+    /// Keeps the selection and looked up offsets of the previous state when it was for the same topic
+    pub fn retain_selection(mut self, previous: &Option<PartitionInfoState>) -> PartitionInfoState {
+        if let Some(previous) = previous.as_ref().filter(|previous| previous.topic == self.topic) {
+            self.selected_index = clamp_index(previous.selected_index, self.partition_metadata.len());
+            self.timestamp_offsets = previous.timestamp_offsets.clone();
+        }
+        self
+    }
+}
+
+#[derive(Clone)]
+pub struct BrokerInfoState {
+    pub broker_metadata: Option<BrokerMetadata>, // None for the cluster-wide defaults
+    pub config_resource: Resource,
+    pub selected_index: usize,
+    pub configs_marked_deleted: Vec<String>,
+    pub configs_marked_modified: Vec<String>,
+}
+
+impl BrokerInfoState {
+    pub fn new(broker_metadata: Option<BrokerMetadata>, config_resource: Resource) -> BrokerInfoState {
+        BrokerInfoState { broker_metadata, config_resource, selected_index: 0, configs_marked_deleted: vec![], configs_marked_modified: vec![] }
+    }
+
+    /// Keeps the selection and marks of the previous state when it was for the same broker (or the cluster-wide defaults)
+    pub fn retain_selection(mut self, previous: &Option<BrokerInfoState>) -> BrokerInfoState {
+        let node_id = |broker_info_state: &BrokerInfoState| broker_info_state.broker_metadata.as_ref().map(|broker_metadata| broker_metadata.node_id);
+        if let Some(previous) = previous.as_ref().filter(|previous| node_id(previous) == node_id(&self)) {
+            self.selected_index = clamp_index(previous.selected_index, self.config_resource.config_entries.len());
+            self.configs_marked_deleted = previous.configs_marked_deleted.clone();
+            self.configs_marked_modified = previous.configs_marked_modified.clone();
+        }
+        self
+    }
+}
+
+fn clamp_index(index: usize, len: usize) -> usize {
+    cmp::min(index, len.saturating_sub(1))
+}
+
+/// Offsets of a topic's partitions at the time they were retrieved
+#[derive(Clone)]
+pub struct OffsetSnapshot {
+    pub retrieved_ms: u64,
+    pub consumer_group: Option<String>,
+    pub partition_offsets: HashMap<i32, i64>,
+    pub consumer_offsets: HashMap<i32, i64>,
+}
+
+impl OffsetSnapshot {
+    pub fn rates_since(&self, previous: &OffsetSnapshot) -> HashMap<i32, OffsetRates> {
+        if self.retrieved_ms <= previous.retrieved_ms {
+            return HashMap::new();
+        }
+
+        let seconds = (self.retrieved_ms - previous.retrieved_ms) as f64 / 1000.0;
+        // committed offsets of different groups can't be compared
+        let same_consumer_group = self.consumer_group.is_some() && self.consumer_group == previous.consumer_group;
+
+        self.partition_offsets
+            .iter()
+            .filter_map(|(partition, offset)| {
+                previous.partition_offsets.get(partition).map(|previous_offset| {
+                    let consumed = match (self.consumer_offsets.get(partition), previous.consumer_offsets.get(partition)) {
+                        (Some(consumer_offset), Some(previous_consumer_offset))
+                            if same_consumer_group && *consumer_offset >= 0 && *previous_consumer_offset >= 0 =>
+                        {
+                            Some((consumer_offset - previous_consumer_offset) as f64 / seconds)
+                        }
+                        _ => None,
+                    };
+                    (*partition, OffsetRates { produced: (offset - previous_offset) as f64 / seconds, consumed })
+                })
+            })
+            .collect()
+    }
+}
+
+/// Messages per second
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OffsetRates {
+    pub produced: f64,
+    pub consumed: Option<f64>, // None without a consumer group
+}
+
+impl OffsetRates {
+    pub fn total(rates: &HashMap<i32, OffsetRates>) -> Option<OffsetRates> {
+        if rates.is_empty() {
+            None
+        } else {
+            let consumed = rates.values().filter_map(|rates| rates.consumed).collect::<Vec<f64>>();
+            Some(OffsetRates {
+                produced: rates.values().map(|rates| rates.produced).sum(),
+                consumed: if consumed.is_empty() { None } else { Some(consumed.iter().sum()) },
+            })
+        }
     }
 }
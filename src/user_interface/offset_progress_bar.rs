@@ -1,6 +1,8 @@
-pub fn new(offset: i64, max_offset: i64, width: i64) -> String {
-    let offset = offset.max(0);
-    let max_offset = max_offset.max(1);
+/// Draws the offset within the range of min_offset (e.g. log start) to max_offset (e.g. log end)
+pub fn new(offset: i64, min_offset: i64, max_offset: i64, width: i64) -> String {
+    let min_offset = min_offset.max(0);
+    let max_offset = (max_offset - min_offset).max(1);
+    let offset = (offset - min_offset).max(0).min(max_offset);
 
     let whole_blocks = (offset * width) / max_offset;
     let last_block = match whole_blocks {
@@ -2,8 +2,12 @@ use std::io::Write;
 
 use termion::{clear, color, cursor, style};
 
+use crate::kafka_protocol::protocol_responses::metadata_response::BrokerMetadata;
 use crate::kafka_protocol::protocol_responses::metadata_response::PartitionMetadata;
+use crate::kafka_protocol::protocol_responses::metadata_response::TopicMetadata;
+use crate::state::OffsetRates;
 use crate::user_interface::offset_progress_bar;
+use crate::util::health::PartitionHealth;
 use crate::util::utils::VecToCSV;
 
 pub struct SelectableList<A>
@@ -46,9 +50,22 @@ pub trait SelectableListItem {
     fn display(&self) -> String;
 }
 
+pub struct TopicOffsets {
+    pub retained_messages: Option<i64>,
+    pub consumer_lag: Option<i64>,
+    pub rates: Option<OffsetRates>,
+}
+
+impl TopicOffsets {
+    fn columns(&self) -> String {
+        format!("{}{}{}", retained_messages_column(&self.retained_messages), consumer_lag_column(&self.consumer_lag), rates_column(&self.rates))
+    }
+}
+
 pub enum TopicListItem<'a> {
-    Normal(&'a str, usize),
-    Internal(&'a str, usize),
+    Normal(&'a str, usize, TopicOffsets),
+    Internal(&'a str, usize, TopicOffsets),
+    Unhealthy(&'a str, usize, TopicOffsets, PartitionHealth),
     Deleted(&'a str, usize),
     Selected(Box<TopicListItem<'a>>),
 }
@@ -56,17 +73,33 @@ pub enum TopicListItem<'a> {
 impl<'a> SelectableListItem for TopicListItem<'a> {
     fn display(&self) -> String {
         match &self {
-            TopicListItem::Normal(label, partitions) => {
-                format!("{}{} [{}{}{}]", color::Fg(color::Cyan), &label, color::Fg(color::LightYellow), partitions, color::Fg(color::Cyan))
-            }
-            TopicListItem::Internal(label, partitions) => format!(
-                "{}{}{} [{}{}{}]",
+            TopicListItem::Normal(label, partitions, offsets) => format!(
+                "{}{} [{}{}{}]{}",
+                color::Fg(color::Cyan),
+                &label,
+                color::Fg(color::LightYellow),
+                partitions,
+                color::Fg(color::Cyan),
+                offsets.columns()
+            ),
+            TopicListItem::Internal(label, partitions, offsets) => format!(
+                "{}{}{} [{}{}{}]{}",
                 color::Fg(color::LightMagenta),
                 &label,
                 color::Fg(color::Cyan),
                 color::Fg(color::LightYellow),
                 partitions,
-                color::Fg(color::Cyan)
+                color::Fg(color::Cyan),
+                offsets.columns()
+            ),
+            TopicListItem::Unhealthy(label, partitions, offsets, health) => format!(
+                "{}{} [{}{}{}]{}",
+                health_color(health),
+                &label,
+                color::Fg(color::LightYellow),
+                partitions,
+                health_color(health),
+                offsets.columns()
             ),
             TopicListItem::Deleted(label, partitions) => {
                 format!("{}{}{} [{}]", color::Fg(color::Black), color::Bg(color::LightRed), &label, partitions)
@@ -76,8 +109,50 @@ impl<'a> SelectableListItem for TopicListItem<'a> {
     }
 }
 
+fn retained_messages_column(retained_messages: &Option<i64>) -> String {
+    match retained_messages {
+        None => String::from(""),
+        Some(retained_messages) => format!(" {}msgs:{}", color::Fg(color::White), retained_messages),
+    }
+}
+
+fn consumer_lag_column(consumer_lag: &Option<i64>) -> String {
+    match consumer_lag {
+        None => String::from(""),
+        Some(0) => format!(" {}lag:0", color::Fg(color::Green)),
+        Some(lag) => format!(" {}lag:{}", color::Fg(color::LightRed), lag),
+    }
+}
+
+fn health_color(health: &PartitionHealth) -> String {
+    match health {
+        PartitionHealth::Offline | PartitionHealth::UnderMinIsr => format!("{}", color::Fg(color::LightRed)),
+        PartitionHealth::UnderReplicated => format!("{}", color::Fg(color::Yellow)),
+    }
+}
+
+fn rates_column(rates: &Option<OffsetRates>) -> String {
+    match rates {
+        None => String::from(""),
+        Some(OffsetRates { produced, consumed }) => format!(
+            " {}in:{:.1}/s{}",
+            color::Fg(color::LightBlue),
+            produced,
+            consumed.map(|consumed| format!(" out:{:.1}/s", consumed)).unwrap_or(String::from(""))
+        ),
+    }
+}
+
 pub enum PartitionListItem<'a> {
-    Normal { partition: i32, partition_metadata: &'a PartitionMetadata, consumer_offset: i64, partition_offset: i64 },
+    Normal {
+        partition: i32,
+        partition_metadata: &'a PartitionMetadata,
+        consumer_offset: i64,
+        log_start_offset: i64,
+        partition_offset: i64,
+        timestamp_offset: Option<i64>,
+        rates: Option<OffsetRates>,
+    },
     Selected(Box<PartitionListItem<'a>>),
 }
 
@@ -85,20 +160,74 @@ impl<'a> SelectableListItem for PartitionListItem<'a> {
     fn display(&self) -> String {
         use self::PartitionListItem::*;
         match &self {
-            Normal { partition, partition_metadata, consumer_offset, partition_offset } => format!(
-                "{}▶ {}{:<4} {}{}{} C:{:10} OF:{:10} L:{} R:{} ISR:{} O:{}{}",
+            Normal { partition, partition_metadata, consumer_offset, log_start_offset, partition_offset, timestamp_offset, rates } => format!(
+                "{}▶ {}{:<4} {}{}{} C:{:10} LS:{:10} OF:{:10}{} RT:{:10} L:{} R:{} ISR:{} O:{}{}{}",
                 color::Fg(color::LightYellow),
                 color::Fg(color::White),
                 partition,
                 color::Fg(color::Green),
-                offset_progress_bar::new(*consumer_offset, *partition_offset, 50),
+                offset_progress_bar::new(*consumer_offset, *log_start_offset, *partition_offset, 50),
                 color::Fg(color::White),
                 if *consumer_offset > 0 { format!("{}", consumer_offset) } else { String::from("--") },
+                if *log_start_offset >= 0 { format!("{}", log_start_offset) } else { String::from("--") },
                 format!("{}", partition_offset),
-                partition_metadata.leader,
+                timestamp_offset_column(timestamp_offset),
+                if *log_start_offset >= 0 { format!("{}", (partition_offset - log_start_offset).max(0)) } else { String::from("--") },
+                leader_column(partition_metadata),
                 partition_metadata.replicas.as_csv(),
                 partition_metadata.isr.as_csv(),
                 color::Fg(color::LightRed),
+                if !partition_metadata.offline_replicas.is_empty() { partition_metadata.offline_replicas.as_csv() } else { String::from("--") },
+                rates_column(rates)
+            ),
+            Selected(item) => format!("{}{}", color::Bg(color::LightBlack), item.display()),
+        }
+    }
+}
+
+/// Offsets looked up by timestamp are -1 when no message is as new as the timestamp, i.e. the log end
+fn timestamp_offset_column(timestamp_offset: &Option<i64>) -> String {
+    match timestamp_offset {
+        None => String::from(""),
+        Some(-1) => format!(" {}AT:{:>10}{}", color::Fg(color::LightBlue), "end", color::Fg(color::White)),
+        Some(offset) => format!(" {}AT:{:10}{}", color::Fg(color::LightBlue), offset, color::Fg(color::White)),
+    }
+}
+
+/// The leader is highlighted when it isn't the preferred (first) replica
+fn leader_column(partition_metadata: &PartitionMetadata) -> String {
+    match partition_metadata.replicas.first() {
+        Some(preferred_leader) if *preferred_leader != partition_metadata.leader => {
+            format!("{}{}{}", color::Fg(color::LightRed), partition_metadata.leader, color::Fg(color::White))
+        }
+        _ => format!("{}", partition_metadata.leader),
+    }
+}
+
+pub enum HealthListItem<'a> {
+    Normal { topic_metadata: &'a TopicMetadata, partition_metadata: &'a PartitionMetadata, health: PartitionHealth },
+    Selected(Box<HealthListItem<'a>>),
+}
+
+impl<'a> SelectableListItem for HealthListItem<'a> {
+    fn display(&self) -> String {
+        use self::HealthListItem::*;
+        match &self {
+            Normal { topic_metadata, partition_metadata, health } => format!(
+                "{}{:<17} {}{}:{} {}L:{} R:{} ISR:{} O:{}",
+                health_color(health),
+                match health {
+                    PartitionHealth::Offline => "offline",
+                    PartitionHealth::UnderMinIsr => "under min ISR",
+                    PartitionHealth::UnderReplicated => "under-replicated",
+                },
+                color::Fg(color::Cyan),
+                topic_metadata.topic,
+                partition_metadata.partition,
+                color::Fg(color::White),
+                partition_metadata.leader,
+                partition_metadata.replicas.as_csv(),
+                partition_metadata.isr.as_csv(),
                 if !partition_metadata.offline_replicas.is_empty() { partition_metadata.offline_replicas.as_csv() } else { String::from("--") }
             ),
             Selected(item) => format!("{}{}", color::Bg(color::LightBlack), item.display()),
@@ -106,6 +235,36 @@ impl<'a> SelectableListItem for PartitionListItem<'a> {
     }
 }
 
+pub enum BrokerListItem<'a> {
+    Normal { broker_metadata: &'a BrokerMetadata, is_controller: bool, leaders: usize, replicas: usize },
+    ClusterDefault,
+    Selected(Box<BrokerListItem<'a>>),
+}
+
+impl<'a> SelectableListItem for BrokerListItem<'a> {
+    fn display(&self) -> String {
+        use self::BrokerListItem::*;
+        match &self {
+            Normal { broker_metadata, is_controller, leaders, replicas } => format!(
+                "{}▶ {}{:<6} {}{}:{} {}rack:{} leaders:{} replicas:{}{}",
+                color::Fg(color::LightYellow),
+                color::Fg(color::White),
+                broker_metadata.node_id,
+                color::Fg(color::Cyan),
+                broker_metadata.host,
+                broker_metadata.port,
+                color::Fg(color::White),
+                broker_metadata.rack.as_ref().map(|rack| rack.as_str()).unwrap_or("--"),
+                leaders,
+                replicas,
+                if *is_controller { format!(" {}[controller]", color::Fg(color::LightMagenta)) } else { String::from("") }
+            ),
+            ClusterDefault => format!("{}▶ {}cluster-wide defaults", color::Fg(color::LightYellow), color::Fg(color::LightMagenta)),
+            Selected(item) => format!("{}{}", color::Bg(color::LightBlack), item.display()),
+        }
+    }
+}
+
 pub enum TopicConfigurationItem {
     Config { name: String, value: Option<String> },
     Selected(Box<TopicConfigurationItem>),
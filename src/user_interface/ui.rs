@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::io::Write;
 
 use termion::clear;
@@ -8,18 +9,26 @@ use termion::terminal_size;
 
 use crate::kafka_protocol::protocol_responses::describeconfigs_response::ConfigEntry;
 use crate::kafka_protocol::protocol_responses::describeconfigs_response::ConfigSource;
+use crate::kafka_protocol::protocol_responses::describeconfigs_response::Resource;
+use crate::kafka_protocol::protocol_responses::metadata_response::BrokerMetadata;
 use crate::kafka_protocol::protocol_responses::metadata_response::MetadataResponse;
 use crate::kafka_protocol::protocol_responses::metadata_response::PartitionMetadata;
 use crate::kafka_protocol::protocol_responses::metadata_response::TopicMetadata;
+use crate::state::BrokerInfoState;
 use crate::state::CurrentView;
 use crate::state::DialogMessage;
+use crate::state::OffsetRates;
 use crate::state::PartitionInfoState;
 use crate::state::State;
 use crate::state::TopicInfoState;
+use crate::user_interface::selectable_list::BrokerListItem;
+use crate::user_interface::selectable_list::HealthListItem;
 use crate::user_interface::selectable_list::PartitionListItem;
 use crate::user_interface::selectable_list::SelectableList;
 use crate::user_interface::selectable_list::TopicConfigurationItem;
 use crate::user_interface::selectable_list::TopicListItem;
+use crate::user_interface::selectable_list::TopicOffsets;
+use crate::util::health;
 use crate::util::paged_vec::PagedVec;
 use crate::util::utils;
 use crate::util::utils::pad_right;
@@ -28,15 +37,27 @@ pub fn update_with_state(state: &State, screen: &mut impl Write) {
     let (width, height): (u16, u16) = terminal_size().unwrap();
 
     if let Some(ref metadata) = state.metadata {
-        show_dialog_header(screen, width, metadata, &state.dialog_message);
+        show_dialog_header(screen, width, state, metadata, &state.dialog_message);
 
         match state.current_view {
             CurrentView::Topics => {
-                show_topics(screen, height - 2, (1, 2), metadata, state.selected_index, &state.marked_deleted);
+                show_topics(
+                    screen,
+                    height - 2,
+                    (1, 2),
+                    metadata,
+                    state.selected_index,
+                    &state.marked_deleted,
+                    &state.topic_consumer_lag,
+                    &state.topic_retained_messages,
+                    &state.offset_rates,
+                    &state.topic_min_isr,
+                );
             }
             CurrentView::Partitions => {
                 if let Some(partition_info_state) = state.partition_info_state.as_ref() {
-                    show_topic_partitions(screen, height - 2, (1, 2), partition_info_state);
+                    let offset_rates = state.selected_topic_name().and_then(|topic| state.offset_rates.get(&topic));
+                    show_topic_partitions(screen, height - 2, (1, 2), partition_info_state, offset_rates);
                 }
             }
             CurrentView::TopicInfo => {
@@ -44,6 +65,17 @@ pub fn update_with_state(state: &State, screen: &mut impl Write) {
                     show_topic_info(screen, (width, height - 4), (1, 2), topic_info);
                 }
             }
+            CurrentView::Brokers => {
+                show_brokers(screen, height - 2, (1, 2), metadata, state.selected_broker_index);
+            }
+            CurrentView::BrokerInfo => {
+                if let Some(ref broker_info) = state.broker_info_state {
+                    show_broker_info(screen, (width, height - 4), (1, 2), metadata, broker_info);
+                }
+            }
+            CurrentView::Health => {
+                show_health(screen, height - 2, (1, 2), metadata, &state.topic_min_isr, state.selected_health_index);
+            }
             CurrentView::HelpScreen => show_help(screen),
         }
     }
@@ -53,18 +85,25 @@ pub fn update_with_state(state: &State, screen: &mut impl Write) {
     screen.flush().unwrap(); // flush complete buffer to screen once
 }
 
-const HELP: [(&str, &str); 17] = [
+const HELP: [(&str, &str); 24] = [
     ("h", "Toggle this screen"),
     ("q", "Quit"),
     ("t", "Toggle topics view"),
     ("i", "Toggle topic config view"),
     ("p", "Toggle partitions view"),
+    ("b", "Toggle brokers view"),
+    ("B", "Toggle broker config view for the selected broker or the cluster-wide defaults"),
+    ("u", "Toggle unhealthy partitions view (offline, under min ISR, under-replicated)"),
     ("/", "Enter search query for topic name"),
     ("n", "Find next search result"),
     ("r", "Refresh. Retrieves metadata from Kafka cluster"),
+    ("a", "Toggle auto-refresh of the current view"),
     ("c", "Create a new topic with [topic]:[partitions]:[replication factor]"),
     (":", "Modify a resource (e.g. topic config) via text input"),
     ("d", "Delete a resource. Will delete a topic or reset a topic config"),
+    ("g", "Switch consumer group. Tab completes previously used groups"),
+    ("o", "Look up partition offsets at a time, given as ISO-8601 (UTC unless an offset is given), HH:MM[:SS] today (UTC) or epoch millis"),
+    ("C", "Switch cluster. Lists the profiles of the config file, Tab completes their names"),
     ("Up⬆", "Move up one topic"),
     ("Down⬇", "Move down one topic"),
     ("PgUp⇞", "Move up ten topics"),
@@ -79,12 +118,47 @@ fn show_help(screen: &mut impl Write) {
     }
 }
 
-fn show_dialog_header(screen: &mut impl Write, width: u16, metadata: &MetadataResponse, message: &Option<DialogMessage>) {
+fn show_dialog_header(screen: &mut impl Write, width: u16, state: &State, metadata: &MetadataResponse, message: &Option<DialogMessage>) {
+    // the connected profile stays visible in front of every dialog
+    let (cluster_label, label_width) = match state.cluster.as_ref() {
+        Some(cluster) => {
+            let label = format!(" {} ", cluster.name);
+            let label_width = label.chars().count() as u16;
+            (format!("{}{}{}{}{}", cluster_color(cluster.color.as_ref()), color::Fg(color::Black), style::Bold, label, style::Reset), label_width)
+        }
+        None => (String::from(""), 0),
+    };
+    let width = width.saturating_sub(label_width);
+
     let dialog = match message.as_ref() {
         None => {
             let cluster_name = metadata.cluster_id.as_ref().map(|s| s.as_str()).unwrap_or("unknown");
-            let header = format!("cluster:{} brokers:{} topics:{}", cluster_name, metadata.brokers.len(), metadata.topic_metadata.len());
-            Some(format!("{}{}{}{}", color::Fg(color::White), cursor::Right(width - (header.len() as u16)), style::Bold, header))
+            let header = format!(
+                "{}cluster:{} brokers:{} topics:{}",
+                refreshed(state.last_refreshed_ms, state.auto_refresh_interval_secs),
+                cluster_name,
+                metadata.brokers.len(),
+                metadata.topic_metadata.len()
+            );
+
+            let summary = health::health_summary(metadata, &state.topic_min_isr);
+            let health_counts = format!(
+                " under-replicated:{} offline:{} under-min-isr:{}",
+                summary.under_replicated,
+                summary.offline,
+                summary.under_min_isr.map(|count| count.to_string()).unwrap_or(String::from("?"))
+            );
+            let health_color = if summary.is_healthy() { format!("{}", color::Fg(color::White)) } else { format!("{}", color::Fg(color::LightRed)) };
+
+            Some(format!(
+                "{}{}{}{}{}{}",
+                color::Fg(color::White),
+                cursor::Right(width.saturating_sub((header.len() + health_counts.len()) as u16)),
+                style::Bold,
+                header,
+                health_color,
+                health_counts
+            ))
         }
         Some(DialogMessage::None) => None,
         Some(DialogMessage::Error(error)) => Some(format!("{}{}", color::Bg(color::LightRed), pad_right(&error, width))),
@@ -93,7 +167,27 @@ fn show_dialog_header(screen: &mut impl Write, width: u16, metadata: &MetadataRe
     };
 
     if let Some(dialog) = dialog {
-        write!(screen, "{}{}{}{}", cursor::Goto(1, 1), clear::CurrentLine, dialog, style::Reset).unwrap();
+        write!(screen, "{}{}{}{}{}", cursor::Goto(1, 1), clear::CurrentLine, cluster_label, dialog, style::Reset).unwrap();
+    }
+}
+
+fn cluster_color(cluster_color: Option<&String>) -> String {
+    match cluster_color.map(|c| c.to_lowercase()).as_ref().map(|c| c.as_str()) {
+        Some("red") => format!("{}", color::Bg(color::LightRed)),
+        Some("yellow") => format!("{}", color::Bg(color::LightYellow)),
+        Some("green") => format!("{}", color::Bg(color::Green)),
+        Some("blue") => format!("{}", color::Bg(color::LightBlue)),
+        Some("magenta") => format!("{}", color::Bg(color::LightMagenta)),
+        Some("cyan") => format!("{}", color::Bg(color::Cyan)),
+        _ => format!("{}", color::Bg(color::White)),
+    }
+}
+
+fn refreshed(last_refreshed_ms: Option<u64>, auto_refresh_interval_secs: Option<u64>) -> String {
+    match (last_refreshed_ms, auto_refresh_interval_secs) {
+        (None, _) => String::from(""),
+        (Some(ms), None) => format!("refreshed:{}Z ", utils::time_of_day(ms)),
+        (Some(ms), Some(interval_secs)) => format!("refreshed:{}Z(every {}s) ", utils::time_of_day(ms), interval_secs),
     }
 }
 
@@ -104,6 +198,10 @@ fn show_topics(
     metadata: &MetadataResponse,
     selected_index: usize,
     marked_deleted: &Vec<String>,
+    topic_consumer_lag: &HashMap<String, i64>,
+    topic_retained_messages: &HashMap<String, i64>,
+    offset_rates: &HashMap<String, HashMap<i32, OffsetRates>>,
+    topic_min_isr: &Option<HashMap<String, i32>>,
 ) {
     use crate::user_interface::selectable_list::TopicListItem::*;
 
@@ -116,13 +214,20 @@ fn show_topics(
             .map(|&(topic_metadata, index)| {
                 let topic_name = topic_metadata.topic.as_str();
                 let partitions = topic_metadata.partition_metadata.len();
+                let offsets = TopicOffsets {
+                    retained_messages: topic_retained_messages.get(&topic_metadata.topic).cloned(),
+                    consumer_lag: topic_consumer_lag.get(&topic_metadata.topic).cloned(),
+                    rates: offset_rates.get(&topic_metadata.topic).and_then(OffsetRates::total),
+                };
 
                 let item = if marked_deleted.contains(&topic_metadata.topic) {
                     Deleted(topic_name, partitions)
+                } else if let Some(topic_health) = health::topic_health(topic_metadata, topic_min_isr) {
+                    Unhealthy(topic_name, partitions, offsets, topic_health)
                 } else if topic_metadata.is_internal {
-                    Internal(topic_name, partitions)
+                    Internal(topic_name, partitions, offsets)
                 } else {
-                    Normal(topic_name, partitions)
+                    Normal(topic_name, partitions, offsets)
                 };
                 if page_index == index {
                     Selected(Box::from(item))
@@ -136,7 +241,13 @@ fn show_topics(
     }
 }
 
-fn show_topic_partitions(screen: &mut impl Write, height: u16, (start_x, start_y): (u16, u16), partition_info_state: &PartitionInfoState) {
+fn show_topic_partitions(
+    screen: &mut impl Write,
+    height: u16,
+    (start_x, start_y): (u16, u16),
+    partition_info_state: &PartitionInfoState,
+    offset_rates: Option<&HashMap<i32, OffsetRates>>,
+) {
     use crate::user_interface::selectable_list::PartitionListItem::*;
 
     let paged = PagedVec::from(&partition_info_state.partition_metadata, height as usize);
@@ -147,10 +258,23 @@ fn show_topic_partitions(screen: &mut impl Write, height: u16, (start_x, start_y
             .iter()
             .map(|&(partition_metadata, index)| {
                 let consumer_offset = partition_info_state.consumer_offsets.get(&partition_metadata.partition).map(|p| p.offset).unwrap_or(-1);
+                let log_start_offset = partition_info_state.log_start_offsets.get(&partition_metadata.partition).map(|p| p.offset).unwrap_or(-1);
                 let partition_offset = partition_info_state.partition_offsets.get(&partition_metadata.partition).map(|p| p.offset).unwrap_or(-1);
+                let timestamp_offset = partition_info_state
+                    .timestamp_offsets
+                    .as_ref()
+                    .map(|timestamp_offsets| timestamp_offsets.offsets.get(&partition_metadata.partition).map(|p| p.offset).unwrap_or(-1));
+                let rates = offset_rates.and_then(|offset_rates| offset_rates.get(&partition_metadata.partition).cloned());
 
-                let item =
-                    Normal { partition: partition_metadata.partition, partition_metadata: &partition_metadata, consumer_offset, partition_offset };
+                let item = Normal {
+                    partition: partition_metadata.partition,
+                    partition_metadata: &partition_metadata,
+                    consumer_offset,
+                    log_start_offset,
+                    partition_offset,
+                    timestamp_offset,
+                    rates,
+                };
                 if page_index == index {
                     Selected(Box::from(item))
                 } else {
@@ -163,9 +287,52 @@ fn show_topic_partitions(screen: &mut impl Write, height: u16, (start_x, start_y
     }
 }
 
-fn show_topic_info(screen: &mut impl Write, (width, height): (u16, u16), (start_x, start_y): (u16, u16), topic_info: &TopicInfoState) {
-    use crate::user_interface::selectable_list::TopicConfigurationItem::*;
+fn show_health(
+    screen: &mut impl Write,
+    height: u16,
+    (start_x, start_y): (u16, u16),
+    metadata: &MetadataResponse,
+    topic_min_isr: &Option<HashMap<String, i32>>,
+    selected_index: usize,
+) {
+    use crate::user_interface::selectable_list::HealthListItem::*;
+
+    let unhealthy_partitions = health::unhealthy_partitions(metadata, topic_min_isr);
+
+    if unhealthy_partitions.is_empty() {
+        write!(
+            screen,
+            "{}{}{}All partitions are healthy{}",
+            cursor::Goto(start_x, start_y),
+            clear::CurrentLine,
+            color::Fg(color::Green),
+            style::Reset
+        )
+        .unwrap();
+        (SelectableList { list: Vec::<HealthListItem>::new() }).display(screen, (start_x, start_y + 1), height - 1);
+    }
+
+    let paged = PagedVec::from(&unhealthy_partitions, height as usize);
 
+    if let Some((page_index, page)) = paged.page(selected_index) {
+        let list_items = page
+            .iter()
+            .zip(0..page.len())
+            .map(|(&&(topic_metadata, partition_metadata, health), index)| {
+                let item = Normal { topic_metadata, partition_metadata, health };
+                if page_index == index {
+                    Selected(Box::from(item))
+                } else {
+                    item
+                }
+            })
+            .collect::<Vec<HealthListItem>>();
+
+        (SelectableList { list: list_items }).display(screen, (start_x, start_y), height);
+    }
+}
+
+fn show_topic_info(screen: &mut impl Write, (width, height): (u16, u16), (start_x, start_y): (u16, u16), topic_info: &TopicInfoState) {
     let ref topic_metadata = topic_info.topic_metadata;
     let ref config_resource = topic_info.config_resource;
 
@@ -181,26 +348,118 @@ fn show_topic_info(screen: &mut impl Write, (width, height): (u16, u16), (start_
     .unwrap();
     write!(screen, "{}", pad_right(&format!("Internal: {}", &(utils::bool_yes_no(topic_metadata.is_internal))), width)).unwrap();
 
-    // configs
-    write!(screen, "{}{}{}", style::Bold, pad_right(&String::from("Configs:"), width), style::Reset).unwrap();
+    show_configs(
+        screen,
+        (width, height),
+        (start_x, start_y + 2),
+        config_resource,
+        topic_info.selected_index,
+        &topic_info.configs_marked_deleted,
+        &topic_info.configs_marked_modified,
+        &[ConfigSource::TopicConfig as i8],
+    );
+}
+
+fn show_brokers(screen: &mut impl Write, height: u16, (start_x, start_y): (u16, u16), metadata: &MetadataResponse, selected_index: usize) {
+    use crate::user_interface::selectable_list::BrokerListItem::*;
+
+    // the cluster-wide defaults (None) are listed after the brokers
+    let brokers = metadata.brokers.iter().map(Some).chain(std::iter::once(None)).collect::<Vec<Option<&BrokerMetadata>>>();
+    let paged = PagedVec::from(&brokers, height as usize);
+
+    if let Some((page_index, page)) = paged.page(selected_index) {
+        let indexed = page.iter().zip(0..page.len()).collect::<Vec<(&&Option<&BrokerMetadata>, usize)>>();
+        let list_items = indexed
+            .iter()
+            .map(|&(broker_metadata, index)| {
+                let item = match broker_metadata {
+                    None => ClusterDefault,
+                    Some(broker_metadata) => {
+                        let partitions = metadata.topic_metadata.iter().flat_map(|topic_metadata| topic_metadata.partition_metadata.iter());
+                        let leaders = partitions.clone().filter(|partition| partition.leader == broker_metadata.node_id).count();
+                        let replicas = partitions.filter(|partition| partition.replicas.contains(&broker_metadata.node_id)).count();
+                        Normal { broker_metadata, is_controller: broker_metadata.node_id == metadata.controller_id, leaders, replicas }
+                    }
+                };
+                if page_index == index {
+                    Selected(Box::from(item))
+                } else {
+                    item
+                }
+            })
+            .collect::<Vec<BrokerListItem>>();
+
+        (SelectableList { list: list_items }).display(screen, (start_x, start_y), height);
+    }
+}
+
+fn show_broker_info(
+    screen: &mut impl Write,
+    (width, height): (u16, u16),
+    (start_x, start_y): (u16, u16),
+    metadata: &MetadataResponse,
+    broker_info: &BrokerInfoState,
+) {
+    let (title, subtitle, override_sources) = match broker_info.broker_metadata {
+        Some(ref broker_metadata) => (
+            format!("Broker: {} ({}:{})", broker_metadata.node_id, broker_metadata.host, broker_metadata.port),
+            format!("Controller: {}", utils::bool_yes_no(broker_metadata.node_id == metadata.controller_id)),
+            vec![ConfigSource::DynamicBrokerConfig as i8, ConfigSource::DynamicDefaultBrokerConfig as i8],
+        ),
+        None => (
+            String::from("Cluster-wide broker defaults"),
+            String::from("Dynamically updatable configs, as seen by the controller"),
+            vec![ConfigSource::DynamicDefaultBrokerConfig as i8],
+        ),
+    };
+
+    // header
+    write!(screen, "{}{}{}{}", cursor::Goto(start_x, start_y), style::Bold, pad_right(&title, width), style::Reset).unwrap();
+    write!(screen, "{}", pad_right(&subtitle, width)).unwrap();
+
+    show_configs(
+        screen,
+        (width, height),
+        (start_x, start_y + 2),
+        &broker_info.config_resource,
+        broker_info.selected_index,
+        &broker_info.configs_marked_deleted,
+        &broker_info.configs_marked_modified,
+        &override_sources,
+    );
+}
+
+fn show_configs(
+    screen: &mut impl Write,
+    (width, height): (u16, u16),
+    (start_x, start_y): (u16, u16),
+    config_resource: &Resource,
+    selected_index: usize,
+    configs_marked_deleted: &Vec<String>,
+    configs_marked_modified: &Vec<String>,
+    override_sources: &[i8],
+) {
+    use crate::user_interface::selectable_list::TopicConfigurationItem::*;
+
+    write!(screen, "{}{}{}{}", cursor::Goto(start_x, start_y), style::Bold, pad_right(&String::from("Configs:"), width), style::Reset).unwrap();
     let longest_config_name_len = config_resource.config_entries.iter().map(|config_entry| config_entry.config_name.len()).max().unwrap_or(0) as u16;
     let paged = PagedVec::from(&config_resource.config_entries, (height - 1) as usize);
 
-    if let Some((page_index, page)) = paged.page(topic_info.selected_index) {
+    if let Some((page_index, page)) = paged.page(selected_index) {
         let indexed = page.iter().zip(0..page.len()).collect::<Vec<(&&ConfigEntry, usize)>>();
         let list_items = indexed
             .iter()
             .map(|&(config_entry, index)| {
                 let item = Config { name: pad_right(&config_entry.config_name, longest_config_name_len), value: config_entry.config_value.clone() };
                 let item = if page_index == index { Selected(Box::from(item)) } else { item };
-                let item = if config_entry.config_source == ConfigSource::TopicConfig as i8 { Override(Box::from(item)) } else { item };
-                let item = if topic_info.configs_marked_deleted.contains(&config_entry.config_name) { Deleted(Box::from(item)) } else { item };
-                let item = if topic_info.configs_marked_modified.contains(&config_entry.config_name) { Modified(Box::from(item)) } else { item };
+                let item = if override_sources.contains(&config_entry.config_source) { Override(Box::from(item)) } else { item };
+                let item = if configs_marked_deleted.contains(&config_entry.config_name) { Deleted(Box::from(item)) } else { item };
+                let item = if configs_marked_modified.contains(&config_entry.config_name) { Modified(Box::from(item)) } else { item };
                 item
             })
             .collect::<Vec<TopicConfigurationItem>>();
 
-        (SelectableList { list: list_items }).display(screen, (start_x, start_y + 3), height);
+        (SelectableList { list: list_items }).display(screen, (start_x, start_y + 1), height);
     }
 }
 
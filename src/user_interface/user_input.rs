@@ -7,7 +7,17 @@ use termion::input::TermRead;
 use crate::event_bus::Message;
 use crate::event_bus::Message::UserInput;
 
-pub fn read(label: &str, (_cursor_x, _cursor_y): (u16, u16), sender: Sender<Message>) -> Result<Option<String>, ()> {
+pub fn read(label: &str, cursor: (u16, u16), sender: Sender<Message>) -> Result<Option<String>, ()> {
+    read_with_completion(label, cursor, sender, &vec![])
+}
+
+/// Same as `read`, but pressing Tab completes the input against the given candidates
+pub fn read_with_completion(
+    label: &str,
+    (_cursor_x, _cursor_y): (u16, u16),
+    sender: Sender<Message>,
+    candidates: &Vec<String>,
+) -> Result<Option<String>, ()> {
     let stdin = std::io::stdin();
     sender.send(UserInput(String::from(label))).unwrap();
 
@@ -22,6 +32,11 @@ pub fn read(label: &str, (_cursor_x, _cursor_y): (u16, u16), sender: Sender<Mess
             Key::Char('\n') => {
                 break;
             }
+            Key::Char('\t') => {
+                if let Some(completed) = complete(&input.iter().collect::<String>(), candidates) {
+                    input = completed.chars().collect();
+                }
+            }
             Key::Char(c) => {
                 input.push(c);
             }
@@ -47,3 +62,14 @@ pub fn read(label: &str, (_cursor_x, _cursor_y): (u16, u16), sender: Sender<Mess
         Err(())
     }
 }
+
+/// The longest common prefix of all candidates starting with the input
+fn complete(input: &String, candidates: &Vec<String>) -> Option<String> {
+    let matches = candidates.iter().filter(|candidate| candidate.starts_with(input.as_str())).collect::<Vec<&String>>();
+
+    matches.split_first().map(|(first, rest)| {
+        rest.iter().fold(first.to_string(), |prefix, candidate| {
+            prefix.chars().zip(candidate.chars()).take_while(|(a, b)| a == b).map(|(a, _)| a).collect::<String>()
+        })
+    })
+}
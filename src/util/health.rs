@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use topiks_kafka_client::kafka_protocol::protocol_responses::metadata_response::MetadataResponse;
+use topiks_kafka_client::kafka_protocol::protocol_responses::metadata_response::PartitionMetadata;
+use topiks_kafka_client::kafka_protocol::protocol_responses::metadata_response::TopicMetadata;
+
+pub const MIN_INSYNC_REPLICAS: &'static str = "min.insync.replicas";
+
+/// Ordered from most to least severe
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PartitionHealth {
+    Offline,
+    UnderMinIsr,
+    UnderReplicated,
+}
+
+pub struct HealthSummary {
+    pub under_replicated: usize,
+    pub offline: usize,
+    pub under_min_isr: Option<usize>, // None when min.insync.replicas is unknown
+}
+
+impl HealthSummary {
+    pub fn is_healthy(&self) -> bool {
+        self.under_replicated == 0 && self.offline == 0 && self.under_min_isr.unwrap_or(0) == 0
+    }
+}
+
+fn is_offline(partition_metadata: &PartitionMetadata) -> bool {
+    partition_metadata.leader == -1 || !partition_metadata.offline_replicas.is_empty()
+}
+
+fn is_under_min_isr(partition_metadata: &PartitionMetadata, min_isr: Option<i32>) -> bool {
+    min_isr.map(|min_isr| (partition_metadata.isr.len() as i32) < min_isr).unwrap_or(false)
+}
+
+fn is_under_replicated(partition_metadata: &PartitionMetadata) -> bool {
+    partition_metadata.isr.len() < partition_metadata.replicas.len()
+}
+
+/// The most severe problem of a partition, if it has any
+pub fn partition_health(partition_metadata: &PartitionMetadata, min_isr: Option<i32>) -> Option<PartitionHealth> {
+    if is_offline(partition_metadata) {
+        Some(PartitionHealth::Offline)
+    } else if is_under_min_isr(partition_metadata, min_isr) {
+        Some(PartitionHealth::UnderMinIsr)
+    } else if is_under_replicated(partition_metadata) {
+        Some(PartitionHealth::UnderReplicated)
+    } else {
+        None
+    }
+}
+
+/// The most severe problem across all partitions of a topic, if it has any
+pub fn topic_health(topic_metadata: &TopicMetadata, topic_min_isr: &Option<HashMap<String, i32>>) -> Option<PartitionHealth> {
+    let min_isr = min_isr_of(&topic_metadata.topic, topic_min_isr);
+    topic_metadata.partition_metadata.iter().filter_map(|partition_metadata| partition_health(partition_metadata, min_isr)).min()
+}
+
+/// Counts each problem separately, so a partition can be counted as both offline and under-replicated
+pub fn health_summary(metadata: &MetadataResponse, topic_min_isr: &Option<HashMap<String, i32>>) -> HealthSummary {
+    let partitions = metadata
+        .topic_metadata
+        .iter()
+        .flat_map(|topic_metadata| topic_metadata.partition_metadata.iter().map(move |partition_metadata| (topic_metadata, partition_metadata)));
+
+    HealthSummary {
+        under_replicated: partitions.clone().filter(|(_, partition_metadata)| is_under_replicated(partition_metadata)).count(),
+        offline: partitions.clone().filter(|(_, partition_metadata)| is_offline(partition_metadata)).count(),
+        under_min_isr: topic_min_isr.as_ref().map(|_| {
+            partitions
+                .filter(|(topic_metadata, partition_metadata)| is_under_min_isr(partition_metadata, min_isr_of(&topic_metadata.topic, topic_min_isr)))
+                .count()
+        }),
+    }
+}
+
+/// Every partition with a problem, sorted by topic and partition
+pub fn unhealthy_partitions<'a>(
+    metadata: &'a MetadataResponse,
+    topic_min_isr: &Option<HashMap<String, i32>>,
+) -> Vec<(&'a TopicMetadata, &'a PartitionMetadata, PartitionHealth)> {
+    metadata
+        .topic_metadata
+        .iter()
+        .flat_map(|topic_metadata| {
+            let min_isr = min_isr_of(&topic_metadata.topic, topic_min_isr);
+            let mut partitions = topic_metadata
+                .partition_metadata
+                .iter()
+                .filter_map(|partition_metadata| {
+                    partition_health(partition_metadata, min_isr).map(|health| (topic_metadata, partition_metadata, health))
+                })
+                .collect::<Vec<(&TopicMetadata, &PartitionMetadata, PartitionHealth)>>();
+            partitions.sort_by(|(_, a, _), (_, b, _)| a.partition.cmp(&b.partition));
+            partitions
+        })
+        .collect()
+}
+
+fn min_isr_of(topic: &String, topic_min_isr: &Option<HashMap<String, i32>>) -> Option<i32> {
+    topic_min_isr.as_ref().and_then(|topic_min_isr| topic_min_isr.get(topic).cloned())
+}
@@ -1,2 +1,3 @@
+pub mod health;
 pub mod paged_vec;
 pub mod utils;
@@ -1,5 +1,8 @@
 use std::cmp;
 use std::fmt::Display;
+
+use lazy_static::lazy_static;
+use regex::Regex;
 use topiks_kafka_client::kafka_protocol::protocol_responses::metadata_response;
 
 pub fn pad_right(input: &String, width: u16) -> String {
@@ -26,6 +29,115 @@ pub fn current_ms() -> u64 {
     (since_the_epoch.as_secs() * 1000) + (since_the_epoch.subsec_nanos() as u64 / 1_000_000)
 }
 
+/// Time of day (UTC) of the given epoch millis, as HH:MM:SS
+pub fn time_of_day(epoch_ms: u64) -> String {
+    let seconds_of_day = (epoch_ms / 1000) % 86_400;
+    format!("{:02}:{:02}:{:02}", seconds_of_day / 3600, (seconds_of_day % 3600) / 60, seconds_of_day % 60)
+}
+
+lazy_static! {
+    static ref DATETIME_REGEX: Regex =
+        Regex::new(r"^(\d{4})-(\d{2})-(\d{2})(?:[T ](\d{2}):(\d{2})(?::(\d{2})(?:\.(\d{1,3}))?)?)?(Z|[+-]\d{2}:?\d{2})?$").unwrap();
+    static ref TIME_OF_DAY_REGEX: Regex = Regex::new(r"^(\d{2}):(\d{2})(?::(\d{2}))?$").unwrap();
+}
+
+/// Parses epoch millis, an ISO-8601 datetime (e.g. 2019-03-01T09:00:00+01:00) or a time of day today (e.g. 09:00) into epoch millis.
+/// Datetimes without an offset, and times of day, are taken as UTC.
+pub fn parse_timestamp(input: &str) -> Option<i64> {
+    parse_timestamp_on(input, current_ms() as i64 / 86_400_000)
+}
+
+/// Like `parse_timestamp`, with times of day taken on the given days since 1970-01-01
+fn parse_timestamp_on(input: &str, today: i64) -> Option<i64> {
+    let input = input.trim();
+    if input.chars().all(|c| c.is_ascii_digit()) {
+        return input.parse::<i64>().ok();
+    }
+
+    if let Some(captures) = TIME_OF_DAY_REGEX.captures(input) {
+        let number = |index: usize| captures.get(index).map(|m| m.as_str().parse::<i64>().unwrap_or(0)).unwrap_or(0);
+        let (hour, minute, second) = (number(1), number(2), number(3));
+        if hour > 23 || minute > 59 || second > 59 {
+            return None;
+        }
+        return Some((today * 86_400 + hour * 3600 + minute * 60 + second) * 1000);
+    }
+
+    let captures = DATETIME_REGEX.captures(input)?;
+    let number = |index: usize| captures.get(index).map(|m| m.as_str().parse::<i64>().unwrap_or(0)).unwrap_or(0);
+
+    let (year, month, day, hour, minute, second) = (number(1), number(2), number(3), number(4), number(5), number(6));
+    if month < 1 || month > 12 || day < 1 || day > days_in_month(year, month) || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    let millis = captures.get(7).map(|m| format!("{:0<3}", m.as_str()).parse::<i64>().unwrap_or(0)).unwrap_or(0);
+    let offset_minutes = match captures.get(8).map(|m| m.as_str()) {
+        None | Some("Z") => 0,
+        Some(offset) => {
+            let digits = offset[1..].replace(":", "");
+            let minutes = digits[0..2].parse::<i64>().ok()? * 60 + digits[2..4].parse::<i64>().ok()?;
+            if offset.starts_with('-') {
+                -minutes
+            } else {
+                minutes
+            }
+        }
+    };
+
+    let seconds = days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second - offset_minutes * 60;
+    Some(seconds * 1000 + millis).filter(|epoch_ms| *epoch_ms >= 0) // negative timestamps have special meanings in ListOffsets
+}
+
+/// ISO-8601 datetime (UTC) of the given epoch millis
+pub fn iso_8601(epoch_ms: u64) -> String {
+    let epoch_ms = epoch_ms as i64;
+    let (days, millis_of_day) = (epoch_ms / 86_400_000, epoch_ms % 86_400_000);
+    let (year, month, day) = civil_from_days(days);
+    let seconds_of_day = millis_of_day / 1000;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        (seconds_of_day % 3600) / 60,
+        seconds_of_day % 60,
+        millis_of_day % 1000
+    )
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of the given (proleptic Gregorian) date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The (proleptic Gregorian) date of the given days since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = if days >= 0 { days } else { days - 146_096 } / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    (year_of_era + era * 400 + if month <= 2 { 1 } else { 0 }, month, day)
+}
+
 pub fn controller_broker(metadata: &metadata_response::MetadataResponse) -> Option<&metadata_response::BrokerMetadata> {
     metadata
         .brokers
@@ -66,3 +178,29 @@ impl<A> Flatten<A> for Vec<Vec<A>> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_timestamps() {
+        assert_eq!(parse_timestamp("1551427200000"), Some(1_551_427_200_000));
+        assert_eq!(parse_timestamp("2019-03-01"), Some(1_551_398_400_000));
+        assert_eq!(parse_timestamp("2019-03-01T08:00:00Z"), Some(1_551_427_200_000));
+        assert_eq!(parse_timestamp("2019-03-01 08:00:00.5"), Some(1_551_427_200_500));
+        assert_eq!(parse_timestamp("2019-03-01T03:00-0500"), Some(1_551_427_200_000));
+        assert_eq!(parse_timestamp("2019-13-01"), None);
+        assert_eq!(parse_timestamp("2019-02-29"), None);
+        assert_eq!(parse_timestamp("2019-02-31"), None);
+        assert_eq!(parse_timestamp("2020-02-29"), Some(1_582_934_400_000));
+        assert_eq!(parse_timestamp("2019-04-31T09:00:00Z"), None);
+        assert_eq!(parse_timestamp("1969-12-31"), None);
+        // times of day are taken on the given day (2019-03-01)
+        assert_eq!(parse_timestamp_on("09:00", 17_956), Some(1_551_430_800_000));
+        assert_eq!(parse_timestamp_on("08:00:00", 17_956), Some(1_551_427_200_000));
+        assert_eq!(parse_timestamp_on("24:00", 17_956), None);
+        assert_eq!(parse_timestamp_on("9:00", 17_956), None);
+        assert_eq!(iso_8601(1_551_427_200_500), "2019-03-01T08:00:00.500Z");
+    }
+}